@@ -3,60 +3,97 @@
 use core::time;
 use std::{
     cell::RefCell, collections::btree_map::VacantEntry, default, f32::consts::PI, fs, rc::Rc,
-    usize, vec,
+    vec,
 };
 
-use minifb::Key;
+use minifb::{Key, MouseButton};
 
 use crate::engine::{
-    control::keyboard::KeyboardController,
+    control::{keyboard::KeyboardController, mouse::MouseController, orbit::OrbitController},
     engine_3d::Engine3D,
     loader::obj_loader::ObjLoader,
     rendering::{
+        bsp::BspTree,
         camera::Camera3D,
+        fit_mode::FitMode,
+        lod::LodCache,
         mesh::Mesh,
+        palette::Palette,
         palettes::{PALETTE_DEFAULT, PALETTE_PINK, PaletteDefault, PalettePink},
+        post_processing::PostEffect,
+        projection::Projection,
+        toon::ToonShading,
         renderer::Renderer,
         renderer_3d::Renderer3D,
         texture_poll::TexturePool,
     },
+    scene::SceneGraph,
     types::{
+        bounds::Aabb,
+        bvh::Bvh,
         object3d::Object3D,
+        rect::Rect,
         triangle::Triangle,
         vector::{
             matrix4x4::{self, Matrix4x4},
             vector_ops::VectorOps,
+            vector2::Vector2,
             vector3::Vector3,
             vector4::Vector4,
         },
     },
 };
 
+const MONITOR_TEXTURE: &str = "monitor";
+const MONITOR_SIZE: usize = 128;
+/// Radians turned per pixel of mouse movement.
+const MOUSE_SENSITIVITY: f32 = 0.005;
+
 pub struct MyApp {
     pub window: Rc<RefCell<minifb::Window>>,
     pub engine: Engine3D,
     //pub kbcontroller: KeyboardController<'a>,
-    pub objects: Vec<Mesh>,
+    /// Every object drawn, meshes hang from its nodes.
+    pub scene: SceneGraph,
+    /// Hierarchy over the world bounds of the scene meshes, rebuild it when adding or removing some.
+    pub scene_bvh: Bvh,
     pub camera: Camera3D,
+    /// Mouse orbit/pan/zoom around a target, works alongside the fly controls.
+    pub orbit: OrbitController,
     pub f_theta: f32,
-    pub mat_proj: Matrix4x4,
+    pub palette: Box<dyn Palette>,
+    pub show_stats: bool,
+    pub rear_view: bool,
+    /// Off-screen target the monitor camera renders into every frame.
+    pub monitor: Renderer3D,
+    pub monitor_camera: Camera3D,
+    /// Mesh detail levels of each view, kept apart so their hysteresis doesn't interfere.
+    pub lods: LodCache,
+    pub rear_lods: LodCache,
+    pub monitor_lods: LodCache,
+    /// Internal resolution of `FitMode::Fixed`, the one the app starts with.
+    pub base_resolution: (usize, usize),
+    /// Camera picked with O, see `cycle_projection`.
+    pub projection_preset: usize,
+    /// Built from the scene meshes when toggled with B, the scene is then painted in BSP order.
+    pub bsp: Option<BspTree>,
 }
 
 impl MyApp {
     pub fn new(width: usize, height: usize, window: minifb::Window) -> Self {
         let w = Rc::new(RefCell::new(window));
-        let mut objects = vec![];
+        let mut scene = SceneGraph::new();
 
         /*let mut obj = ObjLoader::from_file("/home/deus/Documents/models/african_head.obj").unwrap();
         obj.obj.position.z += 10.0;
         obj.obj.rotation.y += 0.0;
         obj.obj.rotation.x += 0.0;
         obj.obj.rotation.z += 0.0;
-        objects.push(obj);*/
+        scene.add("head", Object3D::zero(), Some(obj), None);*/
 
-        objects.push(Mesh {
-            obj: Object3D::new(Vector3::new(0.0, 0.0, 5.0), Vector3::new(1.0, 10.0, 0.0)),
-            tris: vec![
+        let cube = Mesh::new(
+            Object3D::new(Vector3::new(0.0, 0.0, 5.0), Vector3::new(1.0, 10.0, 0.0)),
+            vec![
                 // SOUTH
                 Triangle::new(
                     Vector4::new(0.0, 0.0, 0.0, 1.0),
@@ -184,274 +221,362 @@ impl MyApp {
                     Vector3::new(1.0, 1.0, 1.0),
                 ]),
             ],
-        });
+        );
+
+        // Screen showing what the monitor camera sees
+        let monitor = Mesh::new(
+            Object3D::new(Vector3::new(-3.0, 0.0, 6.0), Vector3::zero()),
+            vec![
+                Triangle::new(
+                    Vector4::new(0.0, 0.0, 0.0, 1.0),
+                    Vector4::new(0.0, 1.5, 0.0, 1.0),
+                    Vector4::new(2.0, 1.5, 0.0, 1.0),
+                )
+                .set_uv([
+                    Vector3::new(0.0, 1.0, 1.0),
+                    Vector3::new(0.0, 0.0, 1.0),
+                    Vector3::new(1.0, 0.0, 1.0),
+                ]),
+                Triangle::new(
+                    Vector4::new(0.0, 0.0, 0.0, 1.0),
+                    Vector4::new(2.0, 1.5, 0.0, 1.0),
+                    Vector4::new(2.0, 0.0, 0.0, 1.0),
+                )
+                .set_uv([
+                    Vector3::new(0.0, 1.0, 1.0),
+                    Vector3::new(1.0, 0.0, 1.0),
+                    Vector3::new(1.0, 1.0, 1.0),
+                ]),
+            ],
+        )
+        .set_texture(MONITOR_TEXTURE)
+        .set_occluder(true);
+
+        scene.add("cube", Object3D::zero(), Some(cube), None);
+        scene.add("monitor", Object3D::zero(), Some(monitor), None);
+        scene.update();
 
-        let mat_proj = Matrix4x4::project(0.1, 1000.0, 90.0, height, width);
-        let mut texture_poll = TexturePool::new();
+        let texture_poll = TexturePool::new();
         //texture_poll.reg_from_path("test", "/home/deus/Documents/textures/test/facebooklogo.png");
 
         // dbg!(texture_poll.get("test").expect("msg").get_pixel_as_u32(0, 0, false).unwrap());
 
+        let mut renderer = Renderer3D::new(vec![0; width * height], width, height, w.clone());
+
+        // Toggled at runtime with F1..F8
+        let effects = [
+            ("scanlines", PostEffect::Scanlines { spacing: 2, intensity: 0.4 }),
+            ("vignette", PostEffect::Vignette { radius: 0.4, strength: 0.8 }),
+            ("chromatic_aberration", PostEffect::ChromaticAberration { offset: 2 }),
+            ("bloom", PostEffect::Bloom { threshold: 0.7, radius: 4, intensity: 0.8 }),
+            ("grayscale", PostEffect::Grayscale),
+            ("sepia", PostEffect::Sepia { amount: 1.0 }),
+            ("pixelate", PostEffect::Pixelate { size: 4 }),
+            ("fog", PostEffect::DepthFog { color: 0x202020, start: 2.0, end: 20.0 }),
+        ];
+        for (name, effect) in effects {
+            renderer.post_processing.push(name, effect);
+            renderer.post_processing.set_enabled(name, false);
+        }
+
+        // Toggled at runtime with T
+        renderer.toon = Some(ToonShading::default());
+
+        // Looks at the cube from the right
+        let mut monitor_camera = Camera3D::new(Vector3::new(2.5, 1.0, 3.0), MONITOR_SIZE, MONITOR_SIZE);
+        monitor_camera.f_yaw = -0.7;
+
         Self {
             window: w.clone(),
             engine: Engine3D {
                 running: true,
-                renderer: Renderer3D::new(vec![0; width * height], width, height, w.clone()),
+                renderer,
                 kbcontroller: KeyboardController::new(w.clone()),
+                mousecontroller: MouseController::new(w.clone()),
                 texture_poll,
             },
             //kbcontroller: KeyboardController::new(&window)
-            objects,
+            scene_bvh: Bvh::build(&scene.meshes().iter().map(|mesh| mesh.world_bounds()).collect::<Vec<_>>()),
+            scene,
             camera: Camera3D::new(Vector3::new(0.0, 0.0, 0.0), width, height),
+            orbit: OrbitController::new(Vector3::new(0.0, 0.0, 5.0), 5.0),
             //camera: Camera3D::new(Vector3::new(0.0, 0.1, 5.0), width, height),
             f_theta: 0.0,
-            mat_proj,
+            palette: Box::new(PalettePink),
+            show_stats: false,
+            rear_view: false,
+            monitor: Renderer3D::offscreen(MONITOR_SIZE, MONITOR_SIZE),
+            monitor_camera,
+            lods: LodCache::new(),
+            rear_lods: LodCache::new(),
+            monitor_lods: LodCache::new(),
+            base_resolution: (width, height),
+            projection_preset: 0,
+            bsp: None,
         }
     }
 
     pub fn render(&mut self, delta_time: f32) {
-        //println!("FPS: {:.2}", 1.0 / delta_time);
+        // Projections are fitted to the viewport on every draw, only the buffers need resizing
+        self.engine.renderer.fit_to_window();
+
         self.engine.renderer.clear(0);
+        self.engine.renderer.begin_frame();
 
         self.f_theta += 1.0 * delta_time;
-        // self.objects.get_mut(0).unwrap().obj.rotation.y = self.f_theta;
+        // self.scene.local_mut(0).rotation.y = self.f_theta;
+        let moved = self.scene.update();
 
-        for Mesh { obj, tris } in &self.objects {
-            let mut triangles_to_raster: Vec<Triangle> = vec![];
+        // A BSP tree bakes the world space polygons in, moving anything makes it stale
+        if moved && self.bsp.is_some() {
+            self.bsp = Some(BspTree::from_meshes(self.scene.meshes()));
+        }
 
-            for tri in tris {
-                // 1. Rotar triángulo (usando matriz de rotación de objeto)
-                let rotation_matrix = obj.rotation_matrix();
+        // Objects may have moved, keep the tree shape and only update the boxes
+        let bounds: Vec<Aabb> = self.scene.meshes().iter().map(|mesh| mesh.world_bounds()).collect();
+        self.scene_bvh.refit(&bounds);
 
-                // 2. Translate
-                let transform_matrix = obj.transform_matrix();
+        self.render_monitor(delta_time);
 
-                // 3. World Matrix
-                let world_matrix = Matrix4x4::multiply_matrix(&rotation_matrix, &transform_matrix);
+        match &self.bsp {
+            Some(bsp) => {
+                self.engine
+                    .renderer
+                    .draw_bsp(bsp, &self.camera, self.palette.as_ref(), &self.engine.texture_poll)
+            }
+            None => self.engine.renderer.draw_scene(
+                self.scene.meshes(),
+                Some(&self.scene_bvh),
+                &self.camera,
+                self.palette.as_ref(),
+                &self.engine.texture_poll,
+                &mut self.lods,
+            ),
+        }
 
-                // 4. Camera
-                let camera_matrix = self.camera.calc_view();
+        if self.rear_view {
+            self.draw_rear_view();
+        }
 
-                let tri_transformed = Triangle::new(
-                    Matrix4x4::multiply_vec(&world_matrix, &tri.v1).perspective_divide(),
-                    Matrix4x4::multiply_vec(&world_matrix, &tri.v2).perspective_divide(),
-                    Matrix4x4::multiply_vec(&world_matrix, &tri.v3).perspective_divide(),
-                )
-                .set_light_color(0);
+        let show_stats = self.show_stats;
+        self.engine.render(delta_time, |renderer| Self::draw_hud(renderer, delta_time, show_stats));
+    }
 
-                // Calc Normal
-                let l1 = tri_transformed.v2 - tri_transformed.v1;
-                let l2 = tri_transformed.v3 - tri_transformed.v1;
-                let normal = l1.cross(l2).to_vector3().normalize(); // You normally need to normalize a normal
+    /// FPS counter and, when toggled, the last frame statistics.
+    fn draw_hud(renderer: &mut Renderer3D, delta_time: f32, show_stats: bool) {
+        let fps = format!("FPS: {:.2}", 1.0 / delta_time);
+        renderer.draw_text(Vector2::new(4, 4), &fps, PALETTE_DEFAULT::WHITE.to_u32(), 1);
+        if show_stats {
+            let stats = renderer.last_stats.to_string();
+            renderer.draw_text(Vector2::new(4, 16), &stats, PALETTE_DEFAULT::YELLOW.to_u32(), 1);
+        }
+    }
 
-                // Avoid divide by 0 and triangles before camera.
-                if tri_transformed.v1.z <= 0.0
-                    || tri_transformed.v2.z <= 0.0
-                    || tri_transformed.v3.z <= 0.0
-                {
-                    continue;
-                }
+    /// Renders the scene from the monitor camera and publishes it as `MONITOR_TEXTURE`.
+    /// The screen shows itself one frame late.
+    fn render_monitor(&mut self, delta_time: f32) {
+        let monitor = &mut self.monitor;
+        monitor.clear(PALETTE_DEFAULT::BLACK.to_u32());
+        monitor.begin_frame();
+        monitor.draw_scene(
+            self.scene.meshes(),
+            Some(&self.scene_bvh),
+            &self.monitor_camera,
+            self.palette.as_ref(),
+            &self.engine.texture_poll,
+            &mut self.monitor_lods,
+        );
+        monitor.render(delta_time);
+
+        // Registered on the first frame, then updated in place
+        match self.engine.texture_poll.get_mut(MONITOR_TEXTURE) {
+            Some(texture) => monitor.copy_to_texture(texture),
+            None => self
+                .engine
+                .texture_poll
+                .register(MONITOR_TEXTURE, monitor.to_texture(MONITOR_TEXTURE)),
+        }
+    }
 
-                // Get ray from triangle to camera
-                let v_camera_ray = tri_transformed.v1.to_vector3() - self.camera.position;
-
-                // If ray is aligned with normal, make it visible.
-                if normal.dot(v_camera_ray) < 0.0 {
-                    let light_direction = Vector3::new(0.0, 1.0, -1.0).normalize();
-                    let dp = light_direction.dot(normal); // How "aligned" are light direction and triangle sureface normal?
-
-                    let colour: u32 = Renderer3D::get_shading_color(dp, &PalettePink);
-
-                    let viewed_triangle = Triangle::new(
-                        Matrix4x4::multiply_vec(&camera_matrix, &tri_transformed.v1)
-                            .perspective_divide(),
-                        Matrix4x4::multiply_vec(&camera_matrix, &tri_transformed.v2)
-                            .perspective_divide(),
-                        Matrix4x4::multiply_vec(&camera_matrix, &tri_transformed.v3)
-                            .perspective_divide(),
-                    )
-                    .set_light_color(colour);
-
-                    // Clip viewed triangle againts near plane, this could form two aditional triangles.
-
-                    let clipped = Renderer3D::triangle_clip_against_plane(
-                        Vector4 {
-                            x: 0.0,
-                            y: 0.0,
-                            z: 0.2,
-                            w: 1.0,
-                        },
-                        Vector4 {
-                            x: 0.0,
-                            y: 0.0,
-                            z: 1.0,
-                            w: 1.0,
-                        },
-                        &viewed_triangle,
-                    );
-
-                    for tc in clipped {
-                        let mut projected = Triangle::new(
-                            Matrix4x4::multiply_vec(&self.mat_proj, &tc.v1).perspective_divide(),
-                            Matrix4x4::multiply_vec(&self.mat_proj, &tc.v2).perspective_divide(),
-                            Matrix4x4::multiply_vec(&self.mat_proj, &tc.v3).perspective_divide(),
-                        )
-                        .set_light_color(tc.light_color);
-
-                        // Convertir a coordenadas de pantalla
-                        for v in [&mut projected.v1, &mut projected.v2, &mut projected.v3] {
-                            v.x = (v.x + 1.0) * 0.5 * self.engine.renderer.width() as f32;
-                            v.y = (1.0 - v.y) * 0.5 * self.engine.renderer.height() as f32;
-                        }
-
-                        triangles_to_raster.push(projected);
-                    }
-                }
-            }
+    /// Picture-in-picture of what is behind the camera, top right corner.
+    fn draw_rear_view(&mut self) {
+        let renderer = &mut self.engine.renderer;
+        let size = renderer.width().min(renderer.height()) as i32 / 4;
+        let area = Rect::new(renderer.width() as i32 - size - 8, 8, size, size);
+
+        let mut rear_camera = Camera3D::new(self.camera.position, size as usize, size as usize);
+        rear_camera.f_yaw = self.camera.f_yaw + PI;
+        rear_camera.f_pitch = -self.camera.f_pitch;
+        rear_camera.f_roll = -self.camera.f_roll;
+        rear_camera.projection = self.camera.projection;
+
+        renderer.viewport = area;
+        renderer.clear_viewport(PALETTE_DEFAULT::BLACK.to_u32());
+        renderer.draw_scene(
+            self.scene.meshes(),
+            Some(&self.scene_bvh),
+            &rear_camera,
+            self.palette.as_ref(),
+            &self.engine.texture_poll,
+            &mut self.rear_lods,
+        );
+        renderer.viewport = renderer.full_viewport();
+
+        renderer.draw_square(
+            Vector2::new(area.x, area.y) - 1,
+            Vector2::new(area.right(), area.bottom()),
+            PALETTE_DEFAULT::WHITE.to_u32(),
+            false,
+            0,
+        );
+    }
 
-            //  Sort back to front
-            triangles_to_raster.sort_by(|t1, t2| {
-                let z1 = (t1.v1.z + t1.v2.z + t1.v3.z) / 3.0;
-                let z2 = (t2.v1.z + t2.v2.z + t2.v3.z) / 3.0;
-                z1.partial_cmp(&z2)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-                    .reverse() // Back to front (descending order)
-            });
-
-            self.engine.renderer.depth_buffer.fill(0.0);
-
-            // Loop through all transformed, viewed, projected, and sorted triangles
-            for tri_to_raster in triangles_to_raster {
-                let mut tri_queue: Vec<Triangle> = vec![tri_to_raster];
-
-                for edge in 0..4 {
-                    let mut new_triangles: Vec<Triangle> = vec![];
-
-                    for test in tri_queue.drain(..) {
-                        let clipped = match edge {
-                            0 => Renderer3D::triangle_clip_against_plane(
-                                Vector4 {
-                                    x: 0.0,
-                                    y: 0.0,
-                                    z: 0.0,
-                                    w: 1.0,
-                                },
-                                Vector4 {
-                                    x: 0.0,
-                                    y: 1.0,
-                                    z: 0.0,
-                                    w: 1.0,
-                                },
-                                &test,
-                            ),
-                            1 => Renderer3D::triangle_clip_against_plane(
-                                Vector4 {
-                                    x: 0.0,
-                                    y: (self.engine.renderer.height() as f32) - 1.0,
-                                    z: 0.0,
-                                    w: 1.0,
-                                },
-                                Vector4 {
-                                    x: 0.0,
-                                    y: -1.0,
-                                    z: 0.0,
-                                    w: 1.0,
-                                },
-                                &test,
-                            ),
-                            2 => Renderer3D::triangle_clip_against_plane(
-                                Vector4 {
-                                    x: 0.0,
-                                    y: 0.0,
-                                    z: 0.0,
-                                    w: 1.0,
-                                },
-                                Vector4 {
-                                    x: 1.0,
-                                    y: 0.0,
-                                    z: 0.0,
-                                    w: 1.0,
-                                },
-                                &test,
-                            ),
-                            3 => Renderer3D::triangle_clip_against_plane(
-                                Vector4 {
-                                    x: (self.engine.renderer.width() as f32) - 1.0,
-                                    y: 0.0,
-                                    z: 0.0,
-                                    w: 1.0,
-                                },
-                                Vector4 {
-                                    x: -1.0,
-                                    y: 0.0,
-                                    z: 0.0,
-                                    w: 1.0,
-                                },
-                                &test,
-                            ),
-                            _ => vec![],
-                        };
-                        new_triangles.extend(clipped);
-                    }
-
-                    tri_queue = new_triangles;
-                }
-                for t in tri_queue {
-                    self.engine.renderer.fill_triangle(
-                        t.v1.into(),
-                        t.v2.into(),
-                        t.v3.into(),
-                        t.light_color,
-                    );
-                    // self.engine.renderer.textured_triangle(t.v1.into(), t.uv[0], t.v2.into(), t.uv[1], t.v3.into(), t.uv[2], self.engine.texture_poll.get_or_panic("test"));
-
-                    self.engine.renderer.draw_triangle(
-                        t.v1.into(),
-                        t.v2.into(),
-                        t.v3.into(),
-                        PALETTE_DEFAULT::RED.to_u32(),
-                    );
-                }
+    /// Perspective -> orthographic -> isometric -> dimetric -> cabinet, looking at the cube.
+    fn cycle_projection(&mut self) {
+        self.projection_preset = (self.projection_preset + 1) % 5;
+
+        let (width, height) = self.base_resolution;
+        let target = Vector3::new(0.5, 0.5, 5.5);
+        let mut camera = Camera3D::new(Vector3::zero(), width, height);
+        self.camera = match self.projection_preset {
+            1 => {
+                camera.projection = Projection::orthographic(4.0);
+                camera
             }
-        }
-
-        self.engine.render(delta_time);
+            2 => Camera3D::isometric(target, 10.0, 4.0),
+            3 => Camera3D::dimetric(target, 10.0, 4.0),
+            4 => {
+                camera.projection = Projection::cabinet(4.0, target.z);
+                camera
+            }
+            _ => camera,
+        };
     }
 
     pub fn update(&mut self, delta_time: f32) {
         if !self.engine.kbcontroller.is_key_down(Key::Escape) && self.window.borrow().is_open() {
+            // Movement is relative to the view, Shift/Space along world up
             if self.engine.kbcontroller.is_key_down(Key::A) {
-                self.camera.position.x -= 8.0 * delta_time;
+                self.camera.fly(0.0, -8.0 * delta_time, 0.0);
             }
 
             if self.engine.kbcontroller.is_key_down(Key::D) {
-                self.camera.position.x += 8.0 * delta_time;
+                self.camera.fly(0.0, 8.0 * delta_time, 0.0);
             }
 
             if self.engine.kbcontroller.is_key_down(Key::LeftShift) {
-                self.camera.position.y -= 8.0 * delta_time;
+                self.camera.fly(0.0, 0.0, -8.0 * delta_time);
             }
 
             if self.engine.kbcontroller.is_key_down(Key::Space) {
-                self.camera.position.y += 8.0 * delta_time;
+                self.camera.fly(0.0, 0.0, 8.0 * delta_time);
             }
 
-            let v_forward = self.camera.look_dir * (4.0 * delta_time);
-
             if self.engine.kbcontroller.is_key_down(Key::W) {
-                self.camera.position = self.camera.position + v_forward;
+                self.camera.fly(4.0 * delta_time, 0.0, 0.0);
             }
 
             if self.engine.kbcontroller.is_key_down(Key::S) {
-                self.camera.position = self.camera.position - v_forward;
+                self.camera.fly(-4.0 * delta_time, 0.0, 0.0);
             }
 
             if self.engine.kbcontroller.is_key_down(Key::Right) {
-                self.camera.f_yaw += 2.0 * delta_time;
+                self.camera.rotate(2.0 * delta_time, 0.0);
             }
 
             if self.engine.kbcontroller.is_key_down(Key::Left) {
-                self.camera.f_yaw -= 2.0 * delta_time;
+                self.camera.rotate(-2.0 * delta_time, 0.0);
+            }
+
+            if self.engine.kbcontroller.is_key_down(Key::Up) {
+                self.camera.rotate(0.0, 2.0 * delta_time);
+            }
+
+            if self.engine.kbcontroller.is_key_down(Key::Down) {
+                self.camera.rotate(0.0, -2.0 * delta_time);
+            }
+
+            if self.engine.kbcontroller.is_key_down(Key::Q) {
+                self.camera.f_roll += 2.0 * delta_time;
+            }
+
+            if self.engine.kbcontroller.is_key_down(Key::E) {
+                self.camera.f_roll -= 2.0 * delta_time;
+            }
+
+            // Mouse look while the right button is held
+            self.engine.mousecontroller.update();
+            if self.engine.mousecontroller.is_button_down(MouseButton::Right) {
+                let delta = self.engine.mousecontroller.delta() * MOUSE_SENSITIVITY;
+                self.camera.rotate(delta.x, -delta.y);
+            }
+            self.orbit
+                .update(&mut self.camera, &self.engine.mousecontroller, &self.engine.kbcontroller);
+
+            if self.engine.kbcontroller.is_key_pressed(Key::C)
+                && let Some(mesh) = self.scene.meshes().first()
+            {
+                let viewport = self.engine.renderer.viewport;
+                self.orbit.frame(
+                    &mut self.camera,
+                    &mesh.world_bounds(),
+                    viewport.width as usize,
+                    viewport.height as usize,
+                );
+            }
+
+            if self.engine.kbcontroller.is_key_pressed(Key::P) {
+                self.show_stats = !self.show_stats;
+            }
+
+            if self.engine.kbcontroller.is_key_pressed(Key::V) {
+                self.rear_view = !self.rear_view;
+            }
+
+            if self.engine.kbcontroller.is_key_pressed(Key::M) {
+                self.engine.renderer.mode = self.engine.renderer.mode.next();
+            }
+
+            if self.engine.kbcontroller.is_key_pressed(Key::F) {
+                let (width, height) = self.base_resolution;
+                let fit_mode = &mut self.engine.renderer.renderer_2d.fit_mode;
+                *fit_mode = match *fit_mode {
+                    FitMode::Fixed { .. } => FitMode::Stretch { pixel_size: 2 },
+                    FitMode::Stretch { .. } => FitMode::Letterbox { aspect: 4.0 / 3.0, pixel_size: 2 },
+                    FitMode::Letterbox { .. } => FitMode::Fixed { width, height },
+                };
+            }
+
+            // Built once when turned on, `render` rebuilds it when the scene moves
+            if self.engine.kbcontroller.is_key_pressed(Key::B) {
+                self.bsp = match self.bsp {
+                    Some(_) => None,
+                    None => Some(BspTree::from_meshes(self.scene.meshes())),
+                };
+            }
+
+            if self.engine.kbcontroller.is_key_pressed(Key::H) {
+                self.engine.renderer.occlusion_culling = !self.engine.renderer.occlusion_culling;
+            }
+
+            if self.engine.kbcontroller.is_key_pressed(Key::O) {
+                self.cycle_projection();
+            }
+
+            if self.engine.kbcontroller.is_key_pressed(Key::T) {
+                self.engine.renderer.toon = match self.engine.renderer.toon {
+                    Some(_) => None,
+                    None => Some(ToonShading::default()),
+                };
+            }
+
+            let effect_keys = [Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8];
+            for key in self.engine.kbcontroller.get_newly_pressed_keys() {
+                if let Some(i) = effect_keys.iter().position(|k| *k == key)
+                    && let Some(pass) = self.engine.renderer.post_processing.passes.get_mut(i)
+                {
+                    pass.enabled = !pass.enabled;
+                }
             }
         } else {
             self.engine.running = false;
@@ -1 +1,3 @@
-pub mod keyboard;
\ No newline at end of file
+pub mod keyboard;
+pub mod mouse;
+pub mod orbit;
\ No newline at end of file
@@ -0,0 +1,57 @@
+use std::{cell::RefCell, rc::Rc};
+
+use minifb::{MouseButton, MouseMode, Window};
+
+use crate::engine::types::vector::vector2::Vector2;
+
+pub struct MouseController {
+    window: Rc<RefCell<Window>>,
+    last_position: Option<Vector2<f32>>,
+    delta: Vector2<f32>,
+}
+
+impl MouseController {
+    pub fn new(window: Rc<RefCell<Window>>) -> Self {
+        MouseController {
+            window,
+            last_position: None,
+            delta: Vector2::new(0.0, 0.0),
+        }
+    }
+
+    /// Samples the cursor, call once per frame before reading `delta`.
+    pub fn update(&mut self) {
+        let position = self.get_position();
+        self.delta = match (self.last_position, position) {
+            (Some(last), Some(current)) => current - last,
+            _ => Vector2::new(0.0, 0.0),
+        };
+        self.last_position = position;
+    }
+
+    /// Cursor position in window pixels, `None` when outside the window.
+    pub fn get_position(&self) -> Option<Vector2<f32>> {
+        self.window
+            .borrow()
+            .get_mouse_pos(MouseMode::Discard)
+            .map(|(x, y)| Vector2::new(x, y))
+    }
+
+    /// Cursor movement between the last two `update` calls.
+    pub fn delta(&self) -> Vector2<f32> {
+        self.delta
+    }
+
+    /// Wheel movement this frame, positive y scrolls up.
+    pub fn get_scroll(&self) -> Vector2<f32> {
+        self.window
+            .borrow()
+            .get_scroll_wheel()
+            .map(|(x, y)| Vector2::new(x, y))
+            .unwrap_or(Vector2::new(0.0, 0.0))
+    }
+
+    pub fn is_button_down(&self, button: MouseButton) -> bool {
+        self.window.borrow().get_mouse_down(button)
+    }
+}
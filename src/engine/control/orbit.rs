@@ -0,0 +1,118 @@
+use minifb::{Key, MouseButton};
+
+use crate::engine::{
+    control::{keyboard::KeyboardController, mouse::MouseController},
+    rendering::{
+        camera::{Camera3D, MAX_PITCH},
+        projection::Projection,
+    },
+    types::{
+        bounds::Aabb,
+        vector::{vector2::Vector2, vector3::Vector3, vector_ops::VectorOps},
+    },
+};
+
+/// Radians turned per pixel dragged.
+const ORBIT_SPEED: f32 = 0.01;
+/// Fraction of the distance panned per pixel dragged.
+const PAN_SPEED: f32 = 0.002;
+/// Fraction of the distance zoomed per scroll step.
+const ZOOM_SPEED: f32 = 0.1;
+/// Extra room left around framed bounds.
+const FRAME_MARGIN: f32 = 1.2;
+
+/// Arcball style controls for inspecting a model: left drag orbits around `target`,
+/// Ctrl + left drag pans and the wheel zooms.
+pub struct OrbitController {
+    pub target: Vector3<f32>,
+    pub distance: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+}
+
+impl OrbitController {
+    pub fn new(target: Vector3<f32>, distance: f32) -> Self {
+        Self {
+            target,
+            distance,
+            min_distance: 0.5,
+            max_distance: 500.0,
+        }
+    }
+
+    /// Reads mouse and keyboard and moves `camera`. When there is no orbit input the target
+    /// follows the camera instead, so other controls (e.g. WASD flying) can move it freely.
+    pub fn update(&mut self, camera: &mut Camera3D, mouse: &MouseController, keyboard: &KeyboardController) {
+        let drag = if mouse.is_button_down(MouseButton::Left) {
+            mouse.delta()
+        } else {
+            Vector2::new(0.0, 0.0)
+        };
+        let scroll = mouse.get_scroll().y;
+
+        if drag.x == 0.0 && drag.y == 0.0 && scroll == 0.0 {
+            self.sync(camera);
+            return;
+        }
+
+        if keyboard.is_key_down(Key::LeftCtrl) || keyboard.is_key_down(Key::RightCtrl) {
+            self.pan(camera, drag.x, drag.y);
+        } else {
+            camera.rotate(-drag.x * ORBIT_SPEED, drag.y * ORBIT_SPEED);
+        }
+        self.zoom(scroll);
+        self.apply(camera);
+    }
+
+    /// Keeps the target at `distance` in front of the camera.
+    pub fn sync(&mut self, camera: &Camera3D) {
+        self.target = camera.position + camera.forward() * self.distance;
+    }
+
+    /// Moves the target in the view plane, `dx`/`dy` in pixels.
+    pub fn pan(&mut self, camera: &Camera3D, dx: f32, dy: f32) {
+        let scale = self.distance * PAN_SPEED;
+        self.target += camera.up() * (dy * scale) - camera.right() * (dx * scale);
+    }
+
+    /// Positive `steps` move closer, the distance changes by a fraction to feel the same at any scale.
+    pub fn zoom(&mut self, steps: f32) {
+        self.distance = (self.distance * (1.0 - ZOOM_SPEED).powf(steps)).clamp(self.min_distance, self.max_distance);
+    }
+
+    /// Places the camera `distance` away from the target, looking at it.
+    pub fn apply(&self, camera: &mut Camera3D) {
+        camera.f_pitch = camera.f_pitch.clamp(-MAX_PITCH, MAX_PITCH);
+        camera.f_roll = 0.0;
+        camera.position = self.target - camera.forward() * self.distance;
+    }
+
+    /// Centers the target on the box and backs off until it fits the camera projection on a
+    /// `width` x `height` viewport, along whichever side is narrower.
+    pub fn frame(&mut self, camera: &mut Camera3D, bounds: &Aabb, width: usize, height: usize) {
+        self.target = bounds.center();
+        let radius = bounds.extents().magnitude().max(0.01) * FRAME_MARGIN;
+        let aspect = width.max(1) as f32 / height.max(1) as f32;
+        // `size` is the visible height, taller than wide viewports need more to fit the width
+        let parallel_size = radius * 2.0 * (1.0 / aspect).max(1.0);
+
+        match &mut camera.projection {
+            Projection::Perspective { fov, .. } => {
+                let half_fov_y = (*fov * 0.5).to_radians();
+                let half_fov_x = (half_fov_y.tan() * aspect).atan();
+                self.distance = radius / half_fov_y.min(half_fov_x).sin();
+            }
+            Projection::Orthographic { size, .. } => {
+                *size = parallel_size;
+                self.distance = radius * 2.0;
+            }
+            Projection::Oblique { size, focus, .. } => {
+                *size = parallel_size;
+                self.distance = radius * 2.0;
+                *focus = self.distance;
+            }
+        }
+        self.distance = self.distance.clamp(self.min_distance, self.max_distance);
+        self.apply(camera);
+    }
+}
@@ -1,14 +1,19 @@
-use crate::engine::{control::keyboard::KeyboardController, rendering::{renderer::Renderer, renderer_3d::Renderer3D, texture_poll::TexturePool}};
+use crate::engine::{control::{keyboard::KeyboardController, mouse::MouseController}, rendering::{renderer_3d::Renderer3D, texture_poll::TexturePool}};
 pub struct Engine3D {
     pub running: bool,
     pub renderer: Renderer3D,
     pub texture_poll: TexturePool,
     pub kbcontroller: KeyboardController,
+    pub mousecontroller: MouseController,
 }
 
 impl Engine3D {
-    pub fn render(&mut self, delta_time: f32) {
-        self.renderer.render(delta_time);
+    /// Finishes the frame, lets `overlay` draw on top of the post-processed image (HUD, text)
+    /// and presents it.
+    pub fn render(&mut self, delta_time: f32, overlay: impl FnOnce(&mut Renderer3D)) {
+        self.renderer.end_frame();
+        overlay(&mut self.renderer);
+        self.renderer.present(delta_time);
     }
     
     pub fn update(&mut self, _delta_time: f32) {
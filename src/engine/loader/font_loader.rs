@@ -0,0 +1,197 @@
+use core::fmt;
+use std::{collections::HashMap, error::Error, fs, path::Path};
+
+use crate::engine::rendering::{
+    color,
+    font::{BitmapFont, Glyph},
+    texture::Texture,
+};
+
+#[derive(Debug)]
+pub struct ParseFontError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseFontError {
+    fn new(line: usize, message: &str) -> Self {
+        Self {
+            line,
+            message: String::from(message),
+        }
+    }
+}
+
+impl fmt::Display for ParseFontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid font (line {}): {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseFontError {}
+
+pub struct FontLoader;
+
+impl FontLoader {
+    /// Loads a grid of fixed size glyphs, laid out left to right and top to bottom in character
+    /// order starting at `first_char` (usually `' '`).
+    pub fn from_glyph_sheet(
+        path: &str,
+        glyph_width: u32,
+        glyph_height: u32,
+        first_char: char,
+    ) -> Result<BitmapFont, Box<dyn Error>> {
+        if glyph_width == 0 || glyph_height == 0 {
+            return Err(Box::new(ParseFontError::new(0, "glyph size can't be zero")));
+        }
+
+        let sheet = Texture::load(path)?;
+        let columns = sheet.width / glyph_width;
+        let rows = sheet.height / glyph_height;
+        let use_alpha = Self::has_transparency(&sheet);
+
+        let mut font = BitmapFont::new(glyph_height as i32);
+        for i in 0..columns * rows {
+            let Some(c) = char::from_u32(first_char as u32 + i) else {
+                break;
+            };
+            let (x, y) = ((i % columns) * glyph_width, (i / columns) * glyph_height);
+
+            font.glyphs.insert(
+                c,
+                Glyph {
+                    width: glyph_width,
+                    height: glyph_height,
+                    x_offset: 0,
+                    y_offset: 0,
+                    x_advance: glyph_width as i32,
+                    mask: Self::read_mask(&sheet, x, y, glyph_width, glyph_height, use_alpha),
+                },
+            );
+        }
+
+        Ok(font)
+    }
+
+    // https://www.angelcode.com/products/bmfont/doc/file_format.html (text format)
+    pub fn from_bmfont(path: &str) -> Result<BitmapFont, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)?;
+        let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+
+        let mut font = BitmapFont::new(0);
+        let mut pages: HashMap<u32, (Texture, bool)> = HashMap::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let mut tokens = line.split_whitespace();
+            let Some(tag) = tokens.next() else {
+                continue;
+            };
+            let attributes = Self::parse_attributes(tokens);
+            let get = |key: &str| -> Result<i32, ParseFontError> {
+                attributes
+                    .get(key)
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| ParseFontError::new(i + 1, &format!("missing or invalid '{}'", key)))
+            };
+
+            match tag {
+                "common" => font.line_height = get("lineHeight")?,
+                "page" => {
+                    let file = attributes
+                        .get("file")
+                        .ok_or_else(|| ParseFontError::new(i + 1, "missing 'file'"))?;
+                    let page_path = directory.join(file);
+                    let texture = Texture::load(&page_path.to_string_lossy())?;
+                    let use_alpha = Self::has_transparency(&texture);
+                    pages.insert(get("id")? as u32, (texture, use_alpha));
+                }
+                "char" => {
+                    let Some(c) = char::from_u32(get("id")? as u32) else {
+                        continue;
+                    };
+                    let page = attributes.get("page").and_then(|p| p.parse().ok()).unwrap_or(0);
+                    let (texture, use_alpha) = pages
+                        .get(&page)
+                        .ok_or_else(|| ParseFontError::new(i + 1, "char references an unknown page"))?;
+                    let (width, height) = (get("width")? as u32, get("height")? as u32);
+
+                    font.glyphs.insert(
+                        c,
+                        Glyph {
+                            width,
+                            height,
+                            x_offset: get("xoffset")?,
+                            y_offset: get("yoffset")?,
+                            x_advance: get("xadvance")?,
+                            mask: Self::read_mask(
+                                texture,
+                                get("x")? as u32,
+                                get("y")? as u32,
+                                width,
+                                height,
+                                *use_alpha,
+                            ),
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        if font.line_height <= 0 {
+            return Err(Box::new(ParseFontError::new(0, "missing 'common' line")));
+        }
+
+        Ok(font)
+    }
+
+    // key=value pairs, values may be quoted
+    fn parse_attributes<'a>(tokens: impl Iterator<Item = &'a str>) -> HashMap<String, String> {
+        let mut attributes = HashMap::new();
+        let mut pending: Option<(String, String)> = None;
+
+        for token in tokens {
+            if let Some((key, mut value)) = pending.take() {
+                // Continuation of a quoted value with spaces
+                value.push(' ');
+                value.push_str(token);
+                if token.ends_with('"') {
+                    attributes.insert(key, value.trim_matches('"').to_string());
+                } else {
+                    pending = Some((key, value));
+                }
+                continue;
+            }
+
+            if let Some((key, value)) = token.split_once('=') {
+                if value.starts_with('"') && (value.len() == 1 || !value.ends_with('"')) {
+                    pending = Some((key.to_string(), value.to_string()));
+                } else {
+                    attributes.insert(key.to_string(), value.trim_matches('"').to_string());
+                }
+            }
+        }
+
+        attributes
+    }
+
+    // Sheets with transparency use alpha, opaque ones use brightness
+    fn has_transparency(texture: &Texture) -> bool {
+        texture.data.chunks_exact(4).any(|px| px[3] < 255)
+    }
+
+    fn read_mask(texture: &Texture, x: u32, y: u32, width: u32, height: u32, use_alpha: bool) -> Vec<bool> {
+        let mut mask = Vec::with_capacity((width * height) as usize);
+        for gy in 0..height {
+            for gx in 0..width {
+                let on = match texture.get_pixel(x + gx, y + gy) {
+                    Some(px) if use_alpha => px[3] > 127,
+                    Some(px) => color::luminance(color::rgb(px[0], px[1], px[2])) > 0.5,
+                    None => false,
+                };
+                mask.push(on);
+            }
+        }
+        mask
+    }
+}
@@ -1 +1,3 @@
-pub mod obj_loader;
\ No newline at end of file
+pub mod obj_loader;
+pub mod palette_loader;
+pub mod font_loader;
@@ -0,0 +1,228 @@
+use core::fmt;
+use std::{error::Error, fs, path::Path};
+
+use crate::engine::rendering::{palette::ColorPalette, texture::Texture};
+
+#[derive(Debug)]
+pub struct ParsePaletteError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParsePaletteError {
+    fn new(line: usize, message: &str) -> Self {
+        Self {
+            line,
+            message: String::from(message),
+        }
+    }
+}
+
+impl fmt::Display for ParsePaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid palette (line {}): {}", self.line, self.message)
+    }
+}
+
+impl Error for ParsePaletteError {}
+
+pub struct PaletteLoader;
+
+impl PaletteLoader {
+    /// Picks the parser from the file extension: `.gpl`, `.pal`, `.hex` or any image format
+    /// supported by `Texture::load` (palette strips).
+    pub fn from_file(path: &str) -> Result<ColorPalette, Box<dyn Error>> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .ok_or_else(|| ParsePaletteError::new(0, "missing file extension"))?;
+
+        match extension.as_str() {
+            "gpl" => Self::from_gpl(&fs::read_to_string(path)?, Self::file_stem(path)),
+            "pal" => Self::from_jasc_pal(&fs::read_to_string(path)?, Self::file_stem(path)),
+            "hex" => Self::from_hex(&fs::read_to_string(path)?, Self::file_stem(path)),
+            _ => Self::from_image(path),
+        }
+    }
+
+    // https://developer.gimp.org/core/standards/gpl/
+    pub fn from_gpl(contents: &str, name: &str) -> Result<ColorPalette, Box<dyn Error>> {
+        let mut lines = contents.lines().enumerate();
+
+        match lines.next() {
+            Some((_, header)) if header.trim() == "GIMP Palette" => {}
+            _ => return Err(Box::new(ParsePaletteError::new(1, "expected 'GIMP Palette' header"))),
+        }
+
+        let mut palette = ColorPalette::new(name, vec![]);
+
+        for (i, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("Columns:") {
+                continue;
+            }
+            if let Some(palette_name) = line.strip_prefix("Name:") {
+                palette.name = String::from(palette_name.trim());
+                continue;
+            }
+
+            // "R G B [color name]"
+            let rgb: Vec<&str> = line.split_whitespace().take(3).collect();
+            palette.colors.push(Self::parse_rgb(&rgb, i + 1)?);
+        }
+
+        Ok(palette)
+    }
+
+    // https://liero.nl/lierohack/docformats/other-jasc.html
+    pub fn from_jasc_pal(contents: &str, name: &str) -> Result<ColorPalette, Box<dyn Error>> {
+        let lines: Vec<&str> = contents.lines().map(|l| l.trim()).collect();
+
+        if lines.first() != Some(&"JASC-PAL") {
+            return Err(Box::new(ParsePaletteError::new(1, "expected 'JASC-PAL' header")));
+        }
+        if lines.get(1) != Some(&"0100") {
+            return Err(Box::new(ParsePaletteError::new(2, "unsupported JASC-PAL version")));
+        }
+
+        let count: usize = lines
+            .get(2)
+            .and_then(|l| l.parse().ok())
+            .ok_or_else(|| ParsePaletteError::new(3, "expected color count"))?;
+
+        let mut palette = ColorPalette::new(name, Vec::with_capacity(count));
+
+        for (i, line) in lines.iter().enumerate().skip(3) {
+            if line.is_empty() {
+                continue;
+            }
+            let rgb: Vec<&str> = line.split_whitespace().collect();
+            palette.colors.push(Self::parse_rgb(&rgb, i + 1)?);
+        }
+
+        if palette.len() != count {
+            return Err(Box::new(ParsePaletteError::new(
+                3,
+                &format!("header says {} colors but {} were found", count, palette.len()),
+            )));
+        }
+
+        Ok(palette)
+    }
+
+    /// One `RRGGBB` color per line, as exported by lospec. A leading `#` is accepted.
+    pub fn from_hex(contents: &str, name: &str) -> Result<ColorPalette, Box<dyn Error>> {
+        let mut palette = ColorPalette::new(name, vec![]);
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let hex = line.strip_prefix('#').unwrap_or(line);
+            if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(Box::new(ParsePaletteError::new(i + 1, "expected RRGGBB")));
+            }
+            let color = u32::from_str_radix(hex, 16)
+                .map_err(|_| ParsePaletteError::new(i + 1, "invalid hex color"))?;
+            palette.colors.push(color);
+        }
+
+        Ok(palette)
+    }
+
+    /// Reads every pixel of an image (left to right, top to bottom), skipping repeated colors,
+    /// so both 1px strips and upscaled swatches work.
+    pub fn from_image(path: &str) -> Result<ColorPalette, Box<dyn Error>> {
+        let texture = Texture::load(path)?;
+        let mut palette = ColorPalette::new(Self::file_stem(path), vec![]);
+
+        for y in 0..texture.height {
+            for x in 0..texture.width {
+                if let Some(color) = texture.get_pixel_as_u32(x, y, false)
+                    && !palette.colors.contains(&color)
+                {
+                    palette.colors.push(color);
+                }
+            }
+        }
+
+        Ok(palette)
+    }
+
+    fn parse_rgb(rgb: &[&str], line: usize) -> Result<u32, ParsePaletteError> {
+        if rgb.len() < 3 {
+            return Err(ParsePaletteError::new(line, "expected 'R G B'"));
+        }
+
+        let mut color = 0;
+        for channel in &rgb[..3] {
+            let value: u8 = channel
+                .parse()
+                .map_err(|_| ParsePaletteError::new(line, "color channel must be in 0..=255"))?;
+            color = (color << 8) | value as u32;
+        }
+        Ok(color)
+    }
+
+    fn file_stem(path: &str) -> &str {
+        Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_gpl() {
+        let gpl = "GIMP Palette\nName: Sunset\nColumns: 2\n# comment\n255   0   0 Red\n  0 128 255\n";
+        let palette = PaletteLoader::from_gpl(gpl, "file").unwrap();
+        assert_eq!(palette.name, "Sunset");
+        assert_eq!(palette.colors, vec![0xFF0000, 0x0080FF]);
+    }
+
+    #[test]
+    fn parses_jasc_pal() {
+        let pal = "JASC-PAL\n0100\n2\n255 255 255\n16 32 48\n";
+        let palette = PaletteLoader::from_jasc_pal(pal, "file").unwrap();
+        assert_eq!(palette.name, "file");
+        assert_eq!(palette.colors, vec![0xFFFFFF, 0x102030]);
+    }
+
+    #[test]
+    fn parses_hex() {
+        let palette = PaletteLoader::from_hex("ff0000\n#00FF00\n\n0000ff\n", "file").unwrap();
+        assert_eq!(palette.colors, vec![0xFF0000, 0x00FF00, 0x0000FF]);
+    }
+
+    #[test]
+    fn rejects_bad_headers() {
+        assert!(PaletteLoader::from_gpl("Not a palette\n0 0 0\n", "file").is_err());
+        assert!(PaletteLoader::from_jasc_pal("JASC\n0100\n1\n0 0 0\n", "file").is_err());
+        assert!(PaletteLoader::from_jasc_pal("JASC-PAL\n0200\n1\n0 0 0\n", "file").is_err());
+    }
+
+    #[test]
+    fn rejects_jasc_count_mismatch() {
+        assert!(PaletteLoader::from_jasc_pal("JASC-PAL\n0100\n3\n0 0 0\n1 1 1\n", "file").is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_range_channels() {
+        assert!(PaletteLoader::from_gpl("GIMP Palette\n256 0 0\n", "file").is_err());
+        assert!(PaletteLoader::from_jasc_pal("JASC-PAL\n0100\n1\n0 -1 0\n", "file").is_err());
+    }
+
+    #[test]
+    fn rejects_signed_hex() {
+        assert!(PaletteLoader::from_hex("+12345\n", "file").is_err());
+        assert!(PaletteLoader::from_hex("-12345\n", "file").is_err());
+        assert!(PaletteLoader::from_hex("12345g\n", "file").is_err());
+    }
+}
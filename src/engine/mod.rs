@@ -2,4 +2,5 @@ pub mod control;
 pub mod loader;
 pub mod rendering;
 pub mod types;
-pub mod engine_3d;
\ No newline at end of file
+pub mod engine_3d;
+pub mod scene;
@@ -0,0 +1,211 @@
+use crate::engine::{
+    rendering::{frustum::Plane, mesh::Mesh, renderer_3d::Renderer3D},
+    types::{
+        triangle::Triangle,
+        vector::{matrix4x4::Matrix4x4, vector3::Vector3, vector4::Vector4, vector_ops::VectorOps},
+    },
+};
+
+/// Distance under which a vertex counts as lying on a splitting plane.
+const PLANE_EPSILON: f32 = 1e-4;
+/// Triangles tried as splitting plane for each node.
+const PLANE_CANDIDATES: usize = 8;
+
+/// World space triangle stored in the tree, with the texture of the mesh it came from.
+#[derive(Clone, PartialEq)]
+pub struct BspPolygon {
+    pub tri: Triangle,
+    pub texture: Option<String>,
+}
+
+pub struct BspNode {
+    /// Plane of the first polygon placed here, its front side faces the same way.
+    pub plane: Plane,
+    /// Polygons lying on the plane, facing either way.
+    pub polygons: Vec<BspPolygon>,
+    pub front: Option<Box<BspNode>>,
+    pub back: Option<Box<BspNode>>,
+}
+
+/// Binary space partition of static geometry. Walking it gives an exact back to front order
+/// from any point of view, so it can be drawn with the painter's algorithm alone.
+/// Build it again whenever the geometry or the mesh transforms change.
+#[derive(Default)]
+pub struct BspTree {
+    pub root: Option<Box<BspNode>>,
+    /// Triangles cut while building, each adds one or two pieces.
+    pub splits: usize,
+}
+
+impl BspTree {
+    pub fn from_meshes(meshes: &[Mesh]) -> Self {
+        let polygons = meshes
+            .iter()
+            .flat_map(|mesh| {
+                let world = mesh.world_matrix();
+                mesh.tris.iter().map(move |tri| BspPolygon {
+                    tri: Triangle::new(
+                        Matrix4x4::multiply_vec(&world, &tri.v1),
+                        Matrix4x4::multiply_vec(&world, &tri.v2),
+                        Matrix4x4::multiply_vec(&world, &tri.v3),
+                    )
+                    .set_uv(tri.uv)
+                    .set_light_color(tri.light_color),
+                    texture: mesh.texture.clone(),
+                })
+            })
+            .collect();
+        Self::build(polygons)
+    }
+
+    pub fn build(polygons: Vec<BspPolygon>) -> Self {
+        let mut tree = Self::default();
+        tree.root = tree.build_node(polygons);
+        tree
+    }
+
+    fn build_node(&mut self, polygons: Vec<BspPolygon>) -> Option<Box<BspNode>> {
+        // Degenerate triangles have no plane and can't be seen anyway
+        let polygons: Vec<BspPolygon> = polygons.into_iter().filter(|p| plane_of(&p.tri).is_some()).collect();
+        if polygons.is_empty() {
+            return None;
+        }
+
+        let plane = choose_plane(&polygons);
+        let mut node = BspNode { plane, polygons: vec![], front: None, back: None };
+        let (mut front, mut back) = (vec![], vec![]);
+
+        for polygon in polygons {
+            match classify(&polygon.tri, &plane) {
+                Side::On => node.polygons.push(polygon),
+                Side::Front => front.push(polygon),
+                Side::Back => back.push(polygon),
+                Side::Spanning => {
+                    self.splits += 1;
+                    let point = Vector4::from_vector3(plane.normal * -plane.d, 1.0);
+                    let normal = Vector4::from_vector3(plane.normal, 1.0);
+                    for (side, plane_n) in [(&mut front, normal), (&mut back, -normal)] {
+                        for tri in Renderer3D::triangle_clip_against_plane(point, plane_n, &polygon.tri) {
+                            side.push(BspPolygon { tri, texture: polygon.texture.clone() });
+                        }
+                    }
+                }
+            }
+        }
+
+        node.front = self.build_node(front);
+        node.back = self.build_node(back);
+        Some(Box::new(node))
+    }
+
+    /// Polygons ordered farthest first as seen from `eye`. For parallel projections pass the
+    /// world space `direction` the camera looks along, the eye is then ignored.
+    pub fn back_to_front(&self, eye: Vector3<f32>, direction: Option<Vector3<f32>>) -> Vec<&BspPolygon> {
+        let mut ordered = vec![];
+        let mut stack: Vec<Visit> = self.root.as_deref().map(Visit::Node).into_iter().collect();
+
+        while let Some(visit) = stack.pop() {
+            let node = match visit {
+                Visit::Node(node) => node,
+                Visit::Polygons(node) => {
+                    ordered.extend(node.polygons.iter());
+                    continue;
+                }
+            };
+
+            // The side holding the camera is drawn last
+            let eye_in_front = match direction {
+                Some(dir) => node.plane.normal.dot(dir) < 0.0,
+                None => node.plane.distance(eye) >= 0.0,
+            };
+            let (near, far) = if eye_in_front {
+                (&node.front, &node.back)
+            } else {
+                (&node.back, &node.front)
+            };
+
+            // Pushed in reverse: far side, this plane, near side
+            if let Some(near) = near {
+                stack.push(Visit::Node(near));
+            }
+            stack.push(Visit::Polygons(node));
+            if let Some(far) = far {
+                stack.push(Visit::Node(far));
+            }
+        }
+        ordered
+    }
+
+    pub fn len(&self) -> usize {
+        fn count(node: &Option<Box<BspNode>>) -> usize {
+            node.as_ref()
+                .map_or(0, |n| n.polygons.len() + count(&n.front) + count(&n.back))
+        }
+        count(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+}
+
+enum Visit<'a> {
+    Node(&'a BspNode),
+    Polygons(&'a BspNode),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Front,
+    Back,
+    On,
+    Spanning,
+}
+
+fn plane_of(tri: &Triangle) -> Option<Plane> {
+    let normal = (tri.v2 - tri.v1).cross3(tri.v3 - tri.v1);
+    if normal.magnitude() < f32::EPSILON {
+        return None;
+    }
+    let normal = normal.normalize();
+    Some(Plane { normal, d: -normal.dot(tri.v1.to_vector3()) })
+}
+
+fn classify(tri: &Triangle, plane: &Plane) -> Side {
+    let (mut front, mut back) = (false, false);
+    for v in [tri.v1, tri.v2, tri.v3] {
+        let distance = plane.distance(v.to_vector3());
+        front |= distance > PLANE_EPSILON;
+        back |= distance < -PLANE_EPSILON;
+    }
+    match (front, back) {
+        (true, true) => Side::Spanning,
+        (true, false) => Side::Front,
+        (false, true) => Side::Back,
+        (false, false) => Side::On,
+    }
+}
+
+// Among a few evenly spaced triangles, the plane cutting the fewest others while keeping
+// both sides balanced
+fn choose_plane(polygons: &[BspPolygon]) -> Plane {
+    let step = (polygons.len() / PLANE_CANDIDATES).max(1);
+    polygons
+        .iter()
+        .step_by(step)
+        .take(PLANE_CANDIDATES)
+        .filter_map(|candidate| plane_of(&candidate.tri))
+        .min_by_key(|plane| {
+            let (mut front, mut back, mut splits) = (0i64, 0i64, 0i64);
+            for polygon in polygons {
+                match classify(&polygon.tri, plane) {
+                    Side::Front => front += 1,
+                    Side::Back => back += 1,
+                    Side::Spanning => splits += 1,
+                    Side::On => {}
+                }
+            }
+            splits * 8 + (front - back).abs()
+        })
+        .expect("build_node only keeps triangles with a plane")
+}
@@ -1,10 +1,29 @@
 #![allow(unused_variables)]
-use crate::engine::types::vector::{matrix4x4::Matrix4x4, vector3::Vector3, vector4::Vector4, vector_ops::VectorOps};
+use std::f32::consts::FRAC_PI_2;
+
+use crate::engine::{
+    rendering::projection::Projection,
+    types::vector::{
+        matrix4x4::{Handedness, Matrix4x4}, quaternion::Quaternion, vector3::Vector3, vector_ops::VectorOps,
+    },
+};
+
+// Tilt of the classic presets, looking down from the horizon
+const ISOMETRIC_PITCH: f32 = 0.615_479_7; // atan(1 / sqrt(2))
+const DIMETRIC_PITCH: f32 = 0.463_647_6; // atan(1 / 2), 2:1 pixel art
+
+/// Keeps the camera from looking straight up or down, where yaw is undefined.
+pub const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
 
 pub struct Camera3D {
     pub position: Vector3<f32>,
-    pub look_dir: Vector3<f32>,
+    /// Left/right angle in radians, 0 looks along +z.
     pub f_yaw: f32,
+    /// Up/down angle in radians, positive looks up. Clamped to `MAX_PITCH` by `rotate`.
+    pub f_pitch: f32,
+    /// Tilt around the view direction in radians, positive leans left.
+    pub f_roll: f32,
+    pub projection: Projection,
     //pub v_target: Vector3<f32>,
 }
 
@@ -12,27 +31,144 @@ impl Camera3D {
     pub fn new(position: Vector3<f32>, width: usize, height: usize) -> Self {
         Self {
             f_yaw: 0.0,
+            f_pitch: 0.0,
+            f_roll: 0.0,
             position,
-            look_dir: Vector3::zero(),
-            
+            projection: Projection::default(),
         }
     }
 
-    pub fn calc_view(&mut self) -> Matrix4x4 {
-        let v_up = Vector3::up();
+    /// Orthographic camera `distance` away from `target`, seen from 45 degrees with equal
+    /// foreshortening on all three axes.
+    pub fn isometric(target: Vector3<f32>, distance: f32, size: f32) -> Self {
+        Self::parallel(target, distance, size, ISOMETRIC_PITCH)
+    }
+
+    /// Like `isometric`, but flatter so edges have the 2:1 slope common in pixel art.
+    pub fn dimetric(target: Vector3<f32>, distance: f32, size: f32) -> Self {
+        Self::parallel(target, distance, size, DIMETRIC_PITCH)
+    }
+
+    fn parallel(target: Vector3<f32>, distance: f32, size: f32, pitch: f32) -> Self {
+        let mut camera = Self::new(target, 0, 0);
+        camera.projection = Projection::orthographic(size);
+        camera.f_yaw = std::f32::consts::FRAC_PI_4;
+        camera.f_pitch = -pitch;
+        camera.position = target - camera.forward() * distance;
+        camera
+    }
+
+    /// Turns the camera, keeping the pitch inside `MAX_PITCH`.
+    pub fn rotate(&mut self, yaw: f32, pitch: f32) {
+        self.f_yaw += yaw;
+        self.f_pitch = (self.f_pitch + pitch).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /// Moves along the view direction and its right vector, `up` is along world up.
+    pub fn fly(&mut self, forward: f32, right: f32, up: f32) {
+        self.position += self.forward() * forward + self.right() * right + Vector3::up() * up;
+    }
+
+    /// Unit vector the camera looks along, from yaw and pitch.
+    pub fn forward(&self) -> Vector3<f32> {
+        let (sin_yaw, cos_yaw) = self.f_yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.f_pitch.sin_cos();
+        Vector3::new(sin_yaw * cos_pitch, sin_pitch, cos_yaw * cos_pitch)
+    }
+
+    /// Unit vector pointing to the right of the view, tilted by the roll.
+    pub fn right(&self) -> Vector3<f32> {
+        let (right, up) = self.unrolled_axes();
+        let (sin, cos) = self.f_roll.sin_cos();
+        right * cos + up * sin
+    }
+
+    /// Unit vector pointing to the top of the view, tilted by the roll.
+    pub fn up(&self) -> Vector3<f32> {
+        let (right, up) = self.unrolled_axes();
+        let (sin, cos) = self.f_roll.sin_cos();
+        up * cos - right * sin
+    }
+
+    // Right and up before applying the roll
+    fn unrolled_axes(&self) -> (Vector3<f32>, Vector3<f32>) {
+        let forward = self.forward();
+        let right = Vector3::up().cross(forward).normalize();
+        (right, forward.cross(right))
+    }
+
+    /// Yaw, pitch and roll as one rotation from view space (x right, y up, z forward) to the
+    /// world, e.g. to `slerp` between two cameras.
+    pub fn orientation(&self) -> Quaternion {
+        let yaw = Quaternion::from_axis_angle(Vector3::up(), self.f_yaw);
+        let pitch = Quaternion::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), -self.f_pitch);
+        let roll = Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), self.f_roll);
+        yaw * pitch * roll
+    }
+
+    /// Sets yaw, pitch and roll to match `orientation`, the pitch clamped to `MAX_PITCH`.
+    pub fn set_orientation(&mut self, orientation: Quaternion) {
+        let forward = orientation.rotate(Vector3::new(0.0, 0.0, 1.0));
+        self.f_yaw = forward.x.atan2(forward.z);
+        self.f_pitch = forward.y.clamp(-1.0, 1.0).asin().clamp(-MAX_PITCH, MAX_PITCH);
+
+        let (right, up) = self.unrolled_axes();
+        let rolled_right = orientation.rotate(Vector3::new(1.0, 0.0, 0.0));
+        self.f_roll = rolled_right.dot(up).atan2(rolled_right.dot(right));
+    }
+
+    pub fn projection_matrix(&self, width: usize, height: usize) -> Matrix4x4 {
+        self.projection.matrix(width, height)
+    }
 
-        let mut v_target = Vector3 {
-            x: 0.0,
-            y: 0.0,
-            z: 1.0,
-        };
+    pub fn calc_view(&self) -> Matrix4x4 {
+        let v_target = self.position + self.forward();
+        Matrix4x4::look_at(self.position, v_target, self.up(), Handedness::Left)
+    }
+}
 
-        let mat_camera_rot = Matrix4x4::rotation_y(self.f_yaw);
-        self.look_dir = Matrix4x4::multiply_vec(&mat_camera_rot, &Vector4::from_vector3(v_target, 1.0)).to_vector3();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        v_target = self.position + self.look_dir;
+    const EPSILON: f32 = 1e-4;
+
+    fn assert_vec_eq(a: Vector3<f32>, b: Vector3<f32>) {
+        assert!((a - b).magnitude() < EPSILON, "{:?} != {:?}", a, b);
+    }
+
+    fn camera(yaw: f32, pitch: f32, roll: f32) -> Camera3D {
+        let mut camera = Camera3D::new(Vector3::new(0.0, 0.0, 0.0), 0, 0);
+        camera.f_yaw = yaw;
+        camera.f_pitch = pitch;
+        camera.f_roll = roll;
+        camera
+    }
+
+    #[test]
+    fn orientation_turns_view_axes_into_camera_axes() {
+        let camera = camera(0.8, -0.4, 0.3);
+        let orientation = camera.orientation();
+        assert_vec_eq(orientation.rotate(Vector3::new(0.0, 0.0, 1.0)), camera.forward());
+        assert_vec_eq(orientation.rotate(Vector3::new(1.0, 0.0, 0.0)), camera.right());
+        assert_vec_eq(orientation.rotate(Vector3::new(0.0, 1.0, 0.0)), camera.up());
+    }
+
+    #[test]
+    fn set_orientation_round_trips() {
+        for (yaw, pitch, roll) in [(0.0, 0.0, 0.0), (0.8, -0.4, 0.3), (-2.5, 1.2, -1.0)] {
+            let mut other = camera(0.0, 0.0, 0.0);
+            other.set_orientation(camera(yaw, pitch, roll).orientation());
+            assert!((other.f_yaw - yaw).abs() < EPSILON);
+            assert!((other.f_pitch - pitch).abs() < EPSILON);
+            assert!((other.f_roll - roll).abs() < EPSILON);
+        }
+    }
 
-        let mat_camera = Matrix4x4::point_at(self.position, v_target, v_up);
-        Matrix4x4::quick_inverse(&mat_camera)
+    #[test]
+    fn set_orientation_clamps_the_pitch() {
+        let mut camera = camera(0.0, 0.0, 0.0);
+        camera.set_orientation(Quaternion::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), -FRAC_PI_2));
+        assert!((camera.f_pitch.abs() - MAX_PITCH).abs() < EPSILON);
     }
 }
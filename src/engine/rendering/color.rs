@@ -0,0 +1,59 @@
+// Helpers for the 0x00RRGGBB colors used by the framebuffer.
+
+pub fn rgb(r: u8, g: u8, b: u8) -> u32 {
+    ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+}
+
+pub fn channels(color: u32) -> (u8, u8, u8) {
+    (
+        ((color >> 16) & 0xFF) as u8,
+        ((color >> 8) & 0xFF) as u8,
+        (color & 0xFF) as u8,
+    )
+}
+
+/// Builds a color from channels in the 0.0..=1.0 range, clamping out of range values.
+pub fn from_f32(r: f32, g: f32, b: f32) -> u32 {
+    let to_u8 = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    rgb(to_u8(r), to_u8(g), to_u8(b))
+}
+
+pub fn to_f32(color: u32) -> (f32, f32, f32) {
+    let (r, g, b) = channels(color);
+    (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+}
+
+/// Perceived brightness (Rec. 601) in 0.0..=1.0.
+pub fn luminance(color: u32) -> f32 {
+    let (r, g, b) = to_f32(color);
+    0.299 * r + 0.587 * g + 0.114 * b
+}
+
+pub fn scale(color: u32, factor: f32) -> u32 {
+    let (r, g, b) = to_f32(color);
+    from_f32(r * factor, g * factor, b * factor)
+}
+
+pub fn lerp(a: u32, b: u32, t: f32) -> u32 {
+    let (ar, ag, ab) = to_f32(a);
+    let (br, bg, bb) = to_f32(b);
+    from_f32(
+        ar + (br - ar) * t,
+        ag + (bg - ag) * t,
+        ab + (bb - ab) * t,
+    )
+}
+
+/// Adds both colors channel by channel, saturating at white.
+pub fn add(a: u32, b: u32) -> u32 {
+    let (ar, ag, ab) = channels(a);
+    let (br, bg, bb) = channels(b);
+    rgb(ar.saturating_add(br), ag.saturating_add(bg), ab.saturating_add(bb))
+}
+
+/// Channel by channel product, used for tinting.
+pub fn multiply(a: u32, b: u32) -> u32 {
+    let (ar, ag, ab) = to_f32(a);
+    let (br, bg, bb) = to_f32(b);
+    from_f32(ar * br, ag * bg, ab * bb)
+}
@@ -0,0 +1,71 @@
+use crate::engine::rendering::color;
+
+/// How `Renderer3D` draws the scene, switchable at runtime for debugging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    #[default]
+    Solid,
+    Wireframe,
+    SolidWireframe,
+    /// Solid, plus one line per face along its normal.
+    FaceNormals,
+    /// Solid, plus one line per vertex along the averaged normal of the faces sharing it.
+    VertexNormals,
+    /// Depth buffer as grayscale, white is closest.
+    Depth,
+    /// Heatmap of how many times each pixel was written this frame.
+    Overdraw,
+}
+
+impl RenderMode {
+    pub const ALL: [RenderMode; 7] = [
+        RenderMode::Solid,
+        RenderMode::Wireframe,
+        RenderMode::SolidWireframe,
+        RenderMode::FaceNormals,
+        RenderMode::VertexNormals,
+        RenderMode::Depth,
+        RenderMode::Overdraw,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn fills(self) -> bool {
+        !matches!(self, RenderMode::Wireframe)
+    }
+
+    pub fn draws_wireframe(self) -> bool {
+        matches!(self, RenderMode::Wireframe | RenderMode::SolidWireframe)
+    }
+}
+
+/// Writes the depth buffer (1/z, 0 = empty) as grayscale, normalized to the visible range.
+pub fn draw_depth(buffer: &mut [u32], depth_buffer: &[f32]) {
+    let (min, max) = depth_buffer
+        .iter()
+        .filter(|d| **d > 0.0)
+        .fold((f32::MAX, f32::MIN), |(lo, hi), &d| (lo.min(d), hi.max(d)));
+
+    let range = (max - min).max(f32::EPSILON);
+    for (pixel, &depth) in buffer.iter_mut().zip(depth_buffer) {
+        *pixel = if depth > 0.0 {
+            // Keep the farthest surface slightly above the black background
+            let l = 0.15 + 0.85 * (depth - min) / range;
+            color::from_f32(l, l, l)
+        } else {
+            0x000000
+        };
+    }
+}
+
+/// Maps write counts to black -> blue -> green -> yellow -> red -> white.
+pub fn draw_overdraw(buffer: &mut [u32], overdraw_buffer: &[u32]) {
+    const HEAT: [u32; 6] = [0x000000, 0x0000FF, 0x00FF00, 0xFFFF00, 0xFF0000, 0xFFFFFF];
+
+    for (pixel, &count) in buffer.iter_mut().zip(overdraw_buffer) {
+        *pixel = HEAT[(count as usize).min(HEAT.len() - 1)];
+    }
+}
@@ -0,0 +1,81 @@
+use crate::engine::types::rect::Rect;
+
+/// How the internal framebuffer is sized and placed when the window changes size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FitMode {
+    /// Internal resolution follows the window, one buffer pixel per `pixel_size` window pixels.
+    Stretch { pixel_size: usize },
+    /// Like `Stretch`, but keeping `aspect` (width / height) with black bars around.
+    Letterbox { aspect: f32, pixel_size: usize },
+    /// Constant internal resolution, upscaled by the biggest integer factor that fits and centered.
+    Fixed { width: usize, height: usize },
+}
+
+impl FitMode {
+    /// Internal resolution to use for a window of `window_width` x `window_height`.
+    pub fn resolution(&self, window_width: usize, window_height: usize) -> (usize, usize) {
+        match *self {
+            FitMode::Stretch { pixel_size } => {
+                let pixel_size = pixel_size.max(1);
+                ((window_width / pixel_size).max(1), (window_height / pixel_size).max(1))
+            }
+            FitMode::Letterbox { aspect, pixel_size } => {
+                let pixel_size = pixel_size.max(1);
+                let (width, height) = (window_width as f32, window_height as f32);
+                let (width, height) = if width / height > aspect {
+                    (height * aspect, height)
+                } else {
+                    (width, width / aspect)
+                };
+                (
+                    ((width as usize) / pixel_size).max(1),
+                    ((height as usize) / pixel_size).max(1),
+                )
+            }
+            FitMode::Fixed { width, height } => (width.max(1), height.max(1)),
+        }
+    }
+
+    /// Window area the internal buffer of `width` x `height` is scaled into.
+    pub fn placement(&self, width: usize, height: usize, window_width: usize, window_height: usize) -> Rect {
+        let scale = match *self {
+            FitMode::Stretch { pixel_size } | FitMode::Letterbox { pixel_size, .. } => pixel_size.max(1),
+            // Never below 1, a window smaller than the buffer shows its center
+            FitMode::Fixed { .. } => (window_width / width).min(window_height / height).max(1),
+        };
+        let (w, h) = ((width * scale) as i32, (height * scale) as i32);
+        Rect::new(
+            (window_width as i32 - w) / 2,
+            (window_height as i32 - h) / 2,
+            w,
+            h,
+        )
+    }
+}
+
+/// Nearest neighbour copy of `src` into the `area` of `dst`, everything outside is cleared to black.
+pub fn present_scaled(
+    src: &[u32],
+    src_width: usize,
+    src_height: usize,
+    dst: &mut [u32],
+    dst_width: usize,
+    dst_height: usize,
+    area: Rect,
+) {
+    dst.fill(0);
+    let visible = area.intersect(&Rect::new(0, 0, dst_width as i32, dst_height as i32));
+    if visible.is_empty() {
+        return;
+    }
+
+    for y in visible.y..visible.bottom() {
+        let sy = (y - area.y) as usize * src_height / area.height as usize;
+        let src_row = &src[sy * src_width..(sy + 1) * src_width];
+        let dst_row = y as usize * dst_width;
+        for x in visible.x..visible.right() {
+            let sx = (x - area.x) as usize * src_width / area.width as usize;
+            dst[dst_row + x as usize] = src_row[sx];
+        }
+    }
+}
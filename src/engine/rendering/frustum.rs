@@ -0,0 +1,75 @@
+use crate::engine::types::{
+    bounds::{Aabb, BoundingSphere},
+    vector::{matrix4x4::Matrix4x4, vector3::Vector3, vector_ops::VectorOps},
+};
+
+/// Plane as `normal . p + d = 0`, with the normal pointing inside.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub normal: Vector3<f32>,
+    pub d: f32,
+}
+
+impl Plane {
+    pub fn distance(&self, point: Vector3<f32>) -> f32 {
+        self.normal.dot(point) + self.d
+    }
+}
+
+/// The six planes bounding what a camera sees, in world space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    // ? https://www.gamedevs.org/uploads/fast-extraction-viewing-frustum-planes-from-world-view-projection-matrix.pdf
+    /// Extracts the planes from `view * projection`, for any projection mapping the visible
+    /// volume to x, y in -1..1 and z in 0..1.
+    pub fn from_matrix(view_proj: &Matrix4x4) -> Self {
+        // Row vectors, so clip space component j is column j
+        let column = |j: usize| [view_proj.m[0][j], view_proj.m[1][j], view_proj.m[2][j], view_proj.m[3][j]];
+        let (x, y, z, w) = (column(0), column(1), column(2), column(3));
+
+        let plane = |c: [f32; 4]| {
+            let normal = Vector3::new(c[0], c[1], c[2]);
+            let length = normal.magnitude();
+            Plane { normal: normal / length, d: c[3] / length }
+        };
+        let add = |a: [f32; 4], b: [f32; 4]| [a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]];
+        let sub = |a: [f32; 4], b: [f32; 4]| [a[0] - b[0], a[1] - b[1], a[2] - b[2], a[3] - b[3]];
+
+        Self {
+            planes: [
+                plane(add(w, x)), // Left
+                plane(sub(w, x)), // Right
+                plane(add(w, y)), // Bottom
+                plane(sub(w, y)), // Top
+                plane(z),         // Near
+                plane(sub(w, z)), // Far
+            ],
+        }
+    }
+
+    /// False if the sphere is completely outside one of the planes.
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        self.planes.iter().all(|p| p.distance(sphere.center) >= -sphere.radius)
+    }
+
+    /// False if the box is completely outside one of the planes. Boxes near a corner of the
+    /// frustum may still pass while being outside.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        if aabb.is_empty() {
+            return false;
+        }
+        self.planes.iter().all(|p| {
+            // Corner furthest along the normal
+            let corner = Vector3::new(
+                if p.normal.x >= 0.0 { aabb.max.x } else { aabb.min.x },
+                if p.normal.y >= 0.0 { aabb.max.y } else { aabb.min.y },
+                if p.normal.z >= 0.0 { aabb.max.z } else { aabb.min.z },
+            );
+            p.distance(corner) >= 0.0
+        })
+    }
+}
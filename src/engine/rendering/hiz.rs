@@ -0,0 +1,68 @@
+use crate::engine::types::rect::Rect;
+
+/// Texels a test may read before moving to a coarser level.
+const MAX_TEST_TEXELS: usize = 16;
+
+/// Hierarchical Z: mip chain of a depth buffer where each texel keeps the farthest depth of
+/// the pixels under it. Depths are 1/z like `Renderer3D::depth_buffer`, so farthest is the
+/// smallest and 0 means nothing was drawn.
+pub struct DepthPyramid {
+    /// Level 0 is the full buffer, each next one is half the size rounded up.
+    pub levels: Vec<Vec<f32>>,
+    pub sizes: Vec<(usize, usize)>,
+}
+
+impl DepthPyramid {
+    pub fn build(depth: &[f32], width: usize, height: usize) -> Self {
+        let mut levels = vec![depth.to_vec()];
+        let mut sizes = vec![(width, height)];
+
+        while let Some(&(w, h)) = sizes.last()
+            && (w > 1 || h > 1)
+        {
+            let (nw, nh) = (w.div_ceil(2), h.div_ceil(2));
+            let prev = &levels[levels.len() - 1];
+            let mut level = vec![0.0; nw * nh];
+            for y in 0..nh {
+                for x in 0..nw {
+                    // Odd sizes repeat the last row or column
+                    let (x0, y0) = (x * 2, y * 2);
+                    let (x1, y1) = ((x0 + 1).min(w - 1), (y0 + 1).min(h - 1));
+                    level[y * nw + x] = prev[y0 * w + x0]
+                        .min(prev[y0 * w + x1])
+                        .min(prev[y1 * w + x0])
+                        .min(prev[y1 * w + x1]);
+                }
+            }
+            levels.push(level);
+            sizes.push((nw, nh));
+        }
+
+        Self { levels, sizes }
+    }
+
+    /// True if everything drawn over `area` (in level 0 pixels) is closer than `nearest`, the
+    /// 1/z of the closest point of whatever is tested. Uses the finest level where the area
+    /// covers a handful of texels.
+    pub fn is_occluded(&self, area: &Rect, nearest: f32) -> bool {
+        if area.width <= 0 || area.height <= 0 {
+            return false;
+        }
+        let (min_x, min_y) = (area.x as usize, area.y as usize);
+        let (max_x, max_y) = ((area.right() - 1) as usize, (area.bottom() - 1) as usize);
+
+        let mut level = 0;
+        while level + 1 < self.levels.len()
+            && ((max_x >> level) - (min_x >> level) + 1) * ((max_y >> level) - (min_y >> level) + 1)
+                > MAX_TEST_TEXELS
+        {
+            level += 1;
+        }
+
+        let (w, h) = self.sizes[level];
+        let texels = &self.levels[level];
+        ((min_y >> level)..=(max_y >> level).min(h - 1)).all(|y| {
+            ((min_x >> level)..=(max_x >> level).min(w - 1)).all(|x| texels[y * w + x] > nearest)
+        })
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::engine::types::{
+    bounds::Aabb,
+    triangle::Triangle,
+    vector::{vector3::Vector3, vector4::Vector4},
+};
+
+/// Simplified version of a mesh, see `Mesh::add_lod`.
+pub struct LodLevel {
+    pub tris: Vec<Triangle>,
+    /// Used once the mesh covers fewer pixels than this on screen, measured as the projected
+    /// diameter of its bounding sphere.
+    pub below: f32,
+}
+
+/// Level each mesh of a scene was last drawn at, by mesh index. Keep one per view, so
+/// cameras looking at the same meshes don't reset each other's hysteresis.
+#[derive(Debug, Clone, Default)]
+pub struct LodCache {
+    levels: Vec<usize>,
+}
+
+impl LodCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Last level of mesh `index`, 0 if it was never drawn.
+    pub fn get(&self, index: usize) -> usize {
+        self.levels.get(index).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, index: usize, level: usize) {
+        if index >= self.levels.len() {
+            self.levels.resize(index + 1, 0);
+        }
+        self.levels[index] = level;
+    }
+}
+
+/// Index into `[full detail, levels...]` for a mesh `size` pixels tall on screen. `levels`
+/// must be sorted by decreasing `below`.
+/// With `hysteresis` > 0 the size has to go that fraction past a threshold before leaving
+/// `current`, so meshes sitting on a threshold don't keep popping.
+pub fn select_lod(levels: &[LodLevel], size: f32, current: usize, hysteresis: f32) -> usize {
+    let select = |size: f32| levels.iter().take_while(|l| size < l.below).count();
+    let target = select(size);
+    if target > current {
+        select(size * (1.0 + hysteresis)).max(current)
+    } else if target < current {
+        select(size * (1.0 - hysteresis)).min(current)
+    } else {
+        current
+    }
+}
+
+/// Vertex clustering: snaps vertices to a grid of `cells` along the longest side of the
+/// mesh, merging those in the same cell into their average, then drops triangles that
+/// collapsed. Fast and good enough for far away meshes, uvs are kept as they were.
+pub fn simplify(tris: &[Triangle], cells: usize) -> Vec<Triangle> {
+    let bounds = Aabb::from_points(
+        tris.iter()
+            .flat_map(|t| [t.v1.to_vector3(), t.v2.to_vector3(), t.v3.to_vector3()]),
+    );
+    let size = bounds.max - bounds.min;
+    let cell_size = size.x.max(size.y).max(size.z) / cells.max(1) as f32;
+    if bounds.is_empty() || cell_size <= 0.0 {
+        return tris.to_vec();
+    }
+
+    let key = |v: &Vector4<f32>| {
+        [
+            ((v.x - bounds.min.x) / cell_size) as i32,
+            ((v.y - bounds.min.y) / cell_size) as i32,
+            ((v.z - bounds.min.z) / cell_size) as i32,
+        ]
+    };
+
+    let mut clusters: HashMap<[i32; 3], (Vector3<f32>, f32)> = HashMap::new();
+    for v in tris.iter().flat_map(|t| [&t.v1, &t.v2, &t.v3]) {
+        let (sum, count) = clusters.entry(key(v)).or_insert((Vector3::new(0.0, 0.0, 0.0), 0.0));
+        *sum += v.to_vector3();
+        *count += 1.0;
+    }
+    let snapped = |v: &Vector4<f32>| {
+        let (sum, count) = clusters[&key(v)];
+        Vector4::from_vector3(sum / count, 1.0)
+    };
+
+    tris.iter()
+        .filter(|t| {
+            let (a, b, c) = (key(&t.v1), key(&t.v2), key(&t.v3));
+            a != b && b != c && a != c
+        })
+        .map(|t| {
+            Triangle::new(snapped(&t.v1), snapped(&t.v2), snapped(&t.v3))
+                .set_uv(t.uv)
+                .set_light_color(t.light_color)
+        })
+        .collect()
+}
@@ -1,6 +1,184 @@
-use crate::engine::types::{object3d::Object3D, triangle::Triangle};
+
+use crate::engine::{
+    rendering::lod::{self, LodLevel},
+    types::{
+        bounds::{Aabb, BoundingSphere},
+        bvh::Bvh,
+        object3d::Object3D,
+        ray::Ray,
+        triangle::Triangle,
+        vector::{matrix3x3::Matrix3x3, matrix4x4::Matrix4x4, vector3::Vector3, vector4::Vector4},
+    },
+};
 
 pub struct Mesh {
     pub obj: Object3D,
-    pub tris: Vec<Triangle>
+    /// World matrix `obj` is relative to, set by `SceneGraph::update`. Identity otherwise.
+    pub parent: Matrix4x4,
+    pub tris: Vec<Triangle>,
+    /// Name of a `TexturePool` entry sampled with the triangle uvs, flat shaded if `None`.
+    pub texture: Option<String>,
+    /// Drawn first when the renderer does occlusion culling, meshes hidden behind occluders
+    /// are skipped. Best for big, simple meshes like walls.
+    pub occluder: bool,
+    /// Coarser versions of `tris`, by decreasing `below`. The renderer picks one from the
+    /// size of the mesh on screen.
+    pub lods: Vec<LodLevel>,
+    /// Fraction of a threshold the screen size must go past to switch level, 0 for none.
+    pub lod_hysteresis: f32,
+    // Object space bounds of `tris`, refreshed by `update_bounds`
+    bounds: Aabb,
+    sphere: BoundingSphere,
+    // Optional hierarchy over `tris` in object space, see `build_bvh`
+    bvh: Option<Bvh>,
+}
+
+impl Mesh {
+    pub fn new(obj: Object3D, tris: Vec<Triangle>) -> Self {
+        let mut mesh = Self {
+            obj,
+            parent: Matrix4x4::identity(),
+            tris,
+            texture: None,
+            occluder: false,
+            lods: vec![],
+            lod_hysteresis: 0.0,
+            bounds: Aabb::empty(),
+            sphere: BoundingSphere::new(Vector3::new(0.0, 0.0, 0.0), 0.0),
+            bvh: None,
+        };
+        mesh.update_bounds();
+        mesh
+    }
+
+    pub fn set_texture(mut self, name: &str) -> Self {
+        self.texture = Some(String::from(name));
+        self
+    }
+
+    pub fn set_occluder(mut self, occluder: bool) -> Self {
+        self.occluder = occluder;
+        self
+    }
+
+    /// Adds a simplified version drawn once the mesh is less than `below` pixels tall.
+    pub fn add_lod(mut self, tris: Vec<Triangle>, below: f32) -> Self {
+        self.lods.push(LodLevel { tris, below });
+        self.lods.sort_by(|a, b| b.below.total_cmp(&a.below));
+        self
+    }
+
+    /// Like `add_lod` with `tris` simplified to a grid of `cells`, see `lod::simplify`.
+    pub fn generate_lod(self, cells: usize, below: f32) -> Self {
+        let tris = lod::simplify(&self.tris, cells);
+        self.add_lod(tris, below)
+    }
+
+    pub fn set_lod_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.lod_hysteresis = hysteresis;
+        self
+    }
+
+    /// Picks the level for a projected `size` in pixels, `current` being the one this view
+    /// drew last time (see `LodCache`).
+    pub fn select_lod(&self, size: f32, current: usize) -> usize {
+        lod::select_lod(&self.lods, size, current, self.lod_hysteresis)
+    }
+
+    /// Triangles of a level, 0 being `tris`.
+    pub fn lod_tris(&self, lod: usize) -> &[Triangle] {
+        match lod {
+            0 => &self.tris,
+            _ => &self.lods[lod - 1].tris,
+        }
+    }
+
+    /// Recomputes the cached bounds, call it after editing `tris`. Rebuilds the BVH if any.
+    pub fn update_bounds(&mut self) {
+        if self.bvh.is_some() {
+            self.build_bvh();
+        }
+
+        let points: Vec<Vector3<f32>> = self
+            .tris
+            .iter()
+            .flat_map(|t| [t.v1.to_vector3(), t.v2.to_vector3(), t.v3.to_vector3()])
+            .collect();
+        self.bounds = Aabb::from_points(points.iter().copied());
+        self.sphere = BoundingSphere::from_points(&points);
+    }
+
+    /// Builds a BVH over the triangles, worth it for big meshes: the renderer then skips
+    /// groups of triangles outside the view and `raycast` gets faster.
+    pub fn build_bvh(&mut self) {
+        self.bvh = Some(Bvh::build(&self.triangle_bounds()));
+    }
+
+    /// Updates the BVH after vertices moved without adding or removing triangles.
+    pub fn refit_bvh(&mut self) {
+        let bounds = self.triangle_bounds();
+        if let Some(bvh) = &mut self.bvh {
+            bvh.refit(&bounds);
+        }
+    }
+
+    pub fn bvh(&self) -> Option<&Bvh> {
+        self.bvh.as_ref()
+    }
+
+    fn triangle_bounds(&self) -> Vec<Aabb> {
+        self.tris
+            .iter()
+            .map(|t| Aabb::from_points([t.v1.to_vector3(), t.v2.to_vector3(), t.v3.to_vector3()]))
+            .collect()
+    }
+
+    /// Closest triangle hit by a world space ray, as `(triangle index, distance)`.
+    pub fn raycast(&self, ray: &Ray) -> Option<(usize, f32)> {
+        // The direction is not renormalized, so distances are the same in object space
+        let local = ray.transform(&self.world_matrix().affine_inverse().ok()?);
+        let hit = |i: usize| {
+            let t = &self.tris[i];
+            local.intersect_triangle(t.v1.to_vector3(), t.v2.to_vector3(), t.v3.to_vector3())
+        };
+
+        match &self.bvh {
+            Some(bvh) => bvh.raycast(&local, hit),
+            None => (0..self.tris.len())
+                .filter_map(|i| hit(i).map(|t| (i, t)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal)),
+        }
+    }
+
+    /// Object space to world space, as used by `Renderer3D::draw_mesh`.
+    pub fn world_matrix(&self) -> Matrix4x4 {
+        Matrix4x4::multiply_matrix(&self.obj.world_matrix(), &self.parent)
+    }
+
+    /// Object space normals to world space, see `Matrix4x4::normal_matrix`.
+    pub fn normal_matrix(&self) -> Matrix3x3 {
+        self.world_matrix().normal_matrix()
+    }
+
+    pub fn local_bounds(&self) -> &Aabb {
+        &self.bounds
+    }
+
+    pub fn local_sphere(&self) -> &BoundingSphere {
+        &self.sphere
+    }
+
+    /// World space box around the mesh, empty without triangles.
+    pub fn world_bounds(&self) -> Aabb {
+        self.bounds.transform(&self.world_matrix())
+    }
+
+    /// The radius grows with the largest stretch of the world matrix, so non-uniform scaling
+    /// gives a loose fit. Still a bound when rotated children sit under scaled parents.
+    pub fn world_sphere(&self) -> BoundingSphere {
+        let world = self.world_matrix();
+        let center = Matrix4x4::multiply_vec(&world, &Vector4::from_vector3(self.sphere.center, 1.0));
+        let scale = Matrix3x3::from_matrix4(&world).max_stretch();
+        BoundingSphere::new(center.to_vector3(), self.sphere.radius * scale)
+    }
 }
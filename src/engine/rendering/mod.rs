@@ -6,4 +6,19 @@ pub mod camera;
 pub mod palette;
 pub mod palettes;
 pub mod texture;
-pub mod texture_poll;
\ No newline at end of file
+pub mod texture_poll;
+pub mod color;
+pub mod post_processing;
+pub mod toon;
+pub mod debug_view;
+pub mod render_stats;
+pub mod font;
+pub mod sprite;
+pub mod shapes;
+pub mod pixel_buffer;
+pub mod fit_mode;
+pub mod projection;
+pub mod frustum;
+pub mod bsp;
+pub mod hiz;
+pub mod lod;
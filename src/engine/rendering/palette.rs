@@ -1,3 +1,47 @@
 pub trait Palette {
     fn get_shading_color(&self, dp: f32) -> u32;
 }
+
+/// Palette built at runtime, usually from a palette file (see `PaletteLoader`).
+/// Colors are ordered from the darkest shade to the brightest one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorPalette {
+    pub name: String,
+    pub colors: Vec<u32>,
+}
+
+impl ColorPalette {
+    pub fn new(name: &str, colors: Vec<u32>) -> Self {
+        Self {
+            name: String::from(name),
+            colors,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<u32> {
+        self.colors.get(index).copied()
+    }
+}
+
+impl Palette for ColorPalette {
+    fn get_shading_color(&self, dp: f32) -> u32 {
+        if self.colors.is_empty() {
+            return 0x000000;
+        }
+
+        // Same mapping as the hardcoded palettes: dp in [0, 1] walks the whole ramp.
+        let index = (dp * (self.colors.len() - 1) as f32) as i32;
+        if index < 0 {
+            return 0x000000;
+        }
+        self.get(index as usize).unwrap_or(0x000000)
+    }
+}
@@ -0,0 +1,165 @@
+use crate::engine::{
+    rendering::color,
+    types::{rect::Rect, vector::vector2::Vector2},
+};
+
+/// Off-screen block of pixels, e.g. a region copied out of the framebuffer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelBuffer {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u32>,
+}
+
+impl PixelBuffer {
+    pub fn new(width: usize, height: usize, color: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    pub fn get_pixel(&self, pos: Vector2<i32>) -> Option<u32> {
+        get_pixel(&self.pixels, self.width, self.height, pos)
+    }
+
+    pub fn set_pixel(&mut self, pos: Vector2<i32>, color: u32) {
+        if let Some(index) = index(self.width, self.height, pos) {
+            self.pixels[index] = color;
+        }
+    }
+
+    pub fn copy_region(&self, region: Rect) -> PixelBuffer {
+        copy_region(&self.pixels, self.width, self.height, region)
+    }
+
+    pub fn paste_region(&mut self, source: &PixelBuffer, pos: Vector2<i32>, transparent: Option<u32>) {
+        paste_region(&mut self.pixels, self.width, self.height, source, pos, transparent);
+    }
+
+    pub fn flood_fill(&mut self, pos: Vector2<i32>, color: u32, tolerance: f32) -> usize {
+        flood_fill(&mut self.pixels, self.width, self.height, pos, color, tolerance)
+    }
+}
+
+// The functions below work on any row-major `width` x `height` buffer, so the framebuffer
+// and `PixelBuffer` share them.
+
+fn index(width: usize, height: usize, pos: Vector2<i32>) -> Option<usize> {
+    if pos.x >= 0 && pos.y >= 0 && (pos.x as usize) < width && (pos.y as usize) < height {
+        Some(pos.y as usize * width + pos.x as usize)
+    } else {
+        None
+    }
+}
+
+pub fn get_pixel(pixels: &[u32], width: usize, height: usize, pos: Vector2<i32>) -> Option<u32> {
+    index(width, height, pos).map(|i| pixels[i])
+}
+
+/// Copies `region` (clamped to the buffer) into a new `PixelBuffer`.
+pub fn copy_region(pixels: &[u32], width: usize, height: usize, region: Rect) -> PixelBuffer {
+    let region = region.intersect(&Rect::new(0, 0, width as i32, height as i32));
+    let mut copy = PixelBuffer::new(region.width as usize, region.height as usize, 0);
+
+    for y in 0..copy.height {
+        let src = (region.y as usize + y) * width + region.x as usize;
+        copy.pixels[y * copy.width..(y + 1) * copy.width].copy_from_slice(&pixels[src..src + copy.width]);
+    }
+    copy
+}
+
+/// Draws `source` with its top left corner at `pos`, skipping pixels equal to `transparent`.
+pub fn paste_region(
+    pixels: &mut [u32],
+    width: usize,
+    height: usize,
+    source: &PixelBuffer,
+    pos: Vector2<i32>,
+    transparent: Option<u32>,
+) {
+    let target = Rect::new(pos.x, pos.y, source.width as i32, source.height as i32)
+        .intersect(&Rect::new(0, 0, width as i32, height as i32));
+
+    for y in target.y..target.bottom() {
+        for x in target.x..target.right() {
+            let color = source.pixels[(y - pos.y) as usize * source.width + (x - pos.x) as usize];
+            if Some(color) != transparent {
+                pixels[y as usize * width + x as usize] = color;
+            }
+        }
+    }
+}
+
+/// Scanline flood fill from `pos`. Pixels join the region when every channel is within
+/// `tolerance` (0.0 exact match, 1.0 anything) of the starting color.
+/// Returns the number of pixels filled.
+pub fn flood_fill(
+    pixels: &mut [u32],
+    width: usize,
+    height: usize,
+    pos: Vector2<i32>,
+    color: u32,
+    tolerance: f32,
+) -> usize {
+    let Some(start) = index(width, height, pos) else {
+        return 0;
+    };
+
+    let target = pixels[start];
+    let max_diff = (tolerance.clamp(0.0, 1.0) * 255.0).round() as i32;
+    let (tr, tg, tb) = color::channels(target);
+    let matches = |c: u32| {
+        let (r, g, b) = color::channels(c);
+        (r as i32 - tr as i32).abs() <= max_diff
+            && (g as i32 - tg as i32).abs() <= max_diff
+            && (b as i32 - tb as i32).abs() <= max_diff
+    };
+
+    // The new color may still match the target with tolerance, so track visited pixels
+    let mut visited = vec![false; width * height];
+    let mut stack = vec![(pos.x as usize, pos.y as usize)];
+    let mut filled = 0;
+
+    while let Some((x, y)) = stack.pop() {
+        let row = y * width;
+        if visited[row + x] || !matches(pixels[row + x]) {
+            continue;
+        }
+
+        // Grow the span left and right
+        let mut left = x;
+        while left > 0 && !visited[row + left - 1] && matches(pixels[row + left - 1]) {
+            left -= 1;
+        }
+        let mut right = x;
+        while right + 1 < width && !visited[row + right + 1] && matches(pixels[row + right + 1]) {
+            right += 1;
+        }
+
+        for i in left..=right {
+            pixels[row + i] = color;
+            visited[row + i] = true;
+        }
+        filled += right - left + 1;
+
+        // Seed the rows above and below, one seed per matching run
+        for ny in [y.wrapping_sub(1), y + 1] {
+            if ny >= height {
+                continue;
+            }
+            let mut in_run = false;
+            for i in left..=right {
+                let idx = ny * width + i;
+                let open = !visited[idx] && matches(pixels[idx]);
+                if open && !in_run {
+                    stack.push((i, ny));
+                }
+                in_run = open;
+            }
+        }
+    }
+
+    filled
+}
@@ -0,0 +1,215 @@
+use crate::engine::rendering::color;
+
+/// Screen space effect applied to the color buffer once the 3D pass is done.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostEffect {
+    /// Darkens every `spacing`-th row, like a CRT monitor.
+    Scanlines { spacing: usize, intensity: f32 },
+    /// Darkens the corners. `radius` is where the falloff starts (0 center, 1 corner).
+    Vignette { radius: f32, strength: f32 },
+    /// Shifts the red and blue channels `offset` pixels apart horizontally.
+    ChromaticAberration { offset: i32 },
+    /// Blurs the pixels brighter than `threshold` and adds them back on top.
+    Bloom { threshold: f32, radius: usize, intensity: f32 },
+    Grayscale,
+    /// Blends towards a sepia tone, `amount` in 0.0..=1.0.
+    Sepia { amount: f32 },
+    /// Averages blocks of `size` x `size` pixels.
+    Pixelate { size: usize },
+    /// Blends towards `color` with view distance, using the depth buffer.
+    DepthFog { color: u32, start: f32, end: f32 },
+}
+
+pub struct PostPass {
+    pub name: String,
+    pub enabled: bool,
+    pub effect: PostEffect,
+}
+
+/// Ordered list of effects, applied first to last.
+#[derive(Default)]
+pub struct PostProcessing {
+    pub passes: Vec<PostPass>,
+}
+
+impl PostProcessing {
+    pub fn new() -> Self {
+        Self { passes: vec![] }
+    }
+
+    pub fn push(&mut self, name: &str, effect: PostEffect) {
+        self.passes.push(PostPass {
+            name: String::from(name),
+            enabled: true,
+            effect,
+        });
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<PostEffect> {
+        let index = self.passes.iter().position(|p| p.name == name)?;
+        Some(self.passes.remove(index).effect)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut PostEffect> {
+        self.passes
+            .iter_mut()
+            .find(|p| p.name == name)
+            .map(|p| &mut p.effect)
+    }
+
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        if let Some(pass) = self.passes.iter_mut().find(|p| p.name == name) {
+            pass.enabled = enabled;
+        }
+    }
+
+    pub fn toggle(&mut self, name: &str) {
+        if let Some(pass) = self.passes.iter_mut().find(|p| p.name == name) {
+            pass.enabled = !pass.enabled;
+        }
+    }
+
+    pub fn apply(&self, buffer: &mut [u32], depth_buffer: &[f32], width: usize, height: usize) {
+        for pass in self.passes.iter().filter(|p| p.enabled) {
+            pass.effect.apply(buffer, depth_buffer, width, height);
+        }
+    }
+}
+
+impl PostEffect {
+    pub fn apply(&self, buffer: &mut [u32], depth_buffer: &[f32], width: usize, height: usize) {
+        match *self {
+            PostEffect::Scanlines { spacing, intensity } => {
+                let spacing = spacing.max(1);
+                for y in (0..height).step_by(spacing) {
+                    for pixel in &mut buffer[y * width..(y + 1) * width] {
+                        *pixel = color::scale(*pixel, 1.0 - intensity);
+                    }
+                }
+            }
+            PostEffect::Vignette { radius, strength } => {
+                let (cx, cy) = (width as f32 * 0.5, height as f32 * 0.5);
+                let max_dist = (cx * cx + cy * cy).sqrt();
+                for y in 0..height {
+                    for x in 0..width {
+                        let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+                        let dist = (dx * dx + dy * dy).sqrt() / max_dist;
+                        let t = ((dist - radius) / (1.0 - radius).max(f32::EPSILON)).clamp(0.0, 1.0);
+                        // Smoothstep falloff
+                        let falloff = t * t * (3.0 - 2.0 * t);
+                        let idx = y * width + x;
+                        buffer[idx] = color::scale(buffer[idx], 1.0 - strength * falloff);
+                    }
+                }
+            }
+            PostEffect::ChromaticAberration { offset } => {
+                let source = buffer.to_vec();
+                let sample = |x: i32, y: usize| {
+                    source[y * width + x.clamp(0, width as i32 - 1) as usize]
+                };
+                for y in 0..height {
+                    for x in 0..width as i32 {
+                        let (r, _, _) = color::channels(sample(x - offset, y));
+                        let (_, g, _) = color::channels(sample(x, y));
+                        let (_, _, b) = color::channels(sample(x + offset, y));
+                        buffer[y * width + x as usize] = color::rgb(r, g, b);
+                    }
+                }
+            }
+            PostEffect::Bloom { threshold, radius, intensity } => {
+                let bright: Vec<u32> = buffer
+                    .iter()
+                    .map(|&c| if color::luminance(c) > threshold { c } else { 0 })
+                    .collect();
+                let blurred = box_blur(&bright, width, height, radius);
+                for (pixel, glow) in buffer.iter_mut().zip(blurred) {
+                    *pixel = color::add(*pixel, color::scale(glow, intensity));
+                }
+            }
+            PostEffect::Grayscale => {
+                for pixel in buffer.iter_mut() {
+                    let l = color::luminance(*pixel);
+                    *pixel = color::from_f32(l, l, l);
+                }
+            }
+            PostEffect::Sepia { amount } => {
+                for pixel in buffer.iter_mut() {
+                    let (r, g, b) = color::to_f32(*pixel);
+                    let sepia = color::from_f32(
+                        0.393 * r + 0.769 * g + 0.189 * b,
+                        0.349 * r + 0.686 * g + 0.168 * b,
+                        0.272 * r + 0.534 * g + 0.131 * b,
+                    );
+                    *pixel = color::lerp(*pixel, sepia, amount);
+                }
+            }
+            PostEffect::Pixelate { size } => {
+                let size = size.max(1);
+                for by in (0..height).step_by(size) {
+                    for bx in (0..width).step_by(size) {
+                        let (y_end, x_end) = ((by + size).min(height), (bx + size).min(width));
+                        let (mut r, mut g, mut b) = (0.0, 0.0, 0.0);
+                        for y in by..y_end {
+                            for x in bx..x_end {
+                                let (pr, pg, pb) = color::to_f32(buffer[y * width + x]);
+                                r += pr;
+                                g += pg;
+                                b += pb;
+                            }
+                        }
+                        let count = ((y_end - by) * (x_end - bx)) as f32;
+                        let average = color::from_f32(r / count, g / count, b / count);
+                        for y in by..y_end {
+                            buffer[y * width + bx..y * width + x_end].fill(average);
+                        }
+                    }
+                }
+            }
+            PostEffect::DepthFog { color: fog_color, start, end } => {
+                for (pixel, &depth) in buffer.iter_mut().zip(depth_buffer) {
+                    // 1/z in view space, like `Renderer3D::depth_buffer`. 0 means nothing was
+                    // drawn there
+                    if depth <= 0.0 {
+                        continue;
+                    }
+                    let distance = 1.0 / depth;
+                    let t = ((distance - start) / (end - start).max(f32::EPSILON)).clamp(0.0, 1.0);
+                    *pixel = color::lerp(*pixel, fog_color, t);
+                }
+            }
+        }
+    }
+}
+
+// Separable box blur, horizontal then vertical.
+fn box_blur(source: &[u32], width: usize, height: usize, radius: usize) -> Vec<u32> {
+    if radius == 0 {
+        return source.to_vec();
+    }
+
+    let blur_line = |get: &dyn Fn(usize) -> u32, len: usize, out: &mut dyn FnMut(usize, u32)| {
+        for i in 0..len {
+            let (lo, hi) = (i.saturating_sub(radius), (i + radius).min(len - 1));
+            let (mut r, mut g, mut b) = (0.0, 0.0, 0.0);
+            for j in lo..=hi {
+                let (pr, pg, pb) = color::to_f32(get(j));
+                r += pr;
+                g += pg;
+                b += pb;
+            }
+            let count = (hi - lo + 1) as f32;
+            out(i, color::from_f32(r / count, g / count, b / count));
+        }
+    };
+
+    let mut horizontal = vec![0; source.len()];
+    for y in 0..height {
+        blur_line(&|x| source[y * width + x], width, &mut |x, c| horizontal[y * width + x] = c);
+    }
+
+    let mut result = vec![0; source.len()];
+    for x in 0..width {
+        blur_line(&|y| horizontal[y * width + x], height, &mut |y, c| result[y * width + x] = c);
+    }
+    result
+}
@@ -0,0 +1,71 @@
+use std::f32::consts::PI;
+
+use crate::engine::types::vector::{matrix4x4::Matrix4x4, vector3::Vector3, vector_ops::VectorOps};
+
+/// How a camera maps view space to the screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// `fov` is the vertical field of view in degrees.
+    Perspective { fov: f32, near: f32, far: f32 },
+    /// Parallel projection, `size` is the visible height in world units.
+    Orthographic { size: f32, near: f32, far: f32 },
+    /// Parallel projection with depth drawn slanted at `angle` (radians) and scaled by
+    /// `depth_scale`: 1.0 is cavalier, 0.5 cabinet. Depth `focus` stays in place.
+    Oblique {
+        size: f32,
+        angle: f32,
+        depth_scale: f32,
+        focus: f32,
+        near: f32,
+        far: f32,
+    },
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Projection::Perspective { fov: 90.0, near: 0.1, far: 1000.0 }
+    }
+}
+
+impl Projection {
+    pub fn orthographic(size: f32) -> Self {
+        Projection::Orthographic { size, near: 0.1, far: 1000.0 }
+    }
+
+    /// Cabinet projection, depth at 45 degrees and half length, as seen in technical drawings.
+    pub fn cabinet(size: f32, focus: f32) -> Self {
+        Projection::Oblique {
+            size,
+            angle: PI / 4.0,
+            depth_scale: 0.5,
+            focus,
+            near: 0.1,
+            far: 1000.0,
+        }
+    }
+
+    pub fn matrix(&self, width: usize, height: usize) -> Matrix4x4 {
+        match *self {
+            Projection::Perspective { fov, near, far } => Matrix4x4::project(near, far, fov, height, width),
+            Projection::Orthographic { size, near, far } => {
+                Matrix4x4::orthographic(near, far, size, height, width)
+            }
+            Projection::Oblique { size, angle, depth_scale, focus, near, far } => {
+                Matrix4x4::oblique(near, far, size, angle, depth_scale, focus, height, width)
+            }
+        }
+    }
+
+    /// View space direction every pixel looks along, `None` when rays leave from the camera
+    /// position instead (perspective).
+    pub fn view_direction(&self) -> Option<Vector3<f32>> {
+        match *self {
+            Projection::Perspective { .. } => None,
+            Projection::Orthographic { .. } => Some(Vector3::new(0.0, 0.0, 1.0)),
+            // Points along this direction land on the same pixel
+            Projection::Oblique { angle, depth_scale, .. } => Some(
+                Vector3::new(-depth_scale * angle.cos(), -depth_scale * angle.sin(), 1.0).normalize(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use core::fmt;
+use std::time::Duration;
+
+/// Pipeline counters for one frame, reset by `Renderer3D::begin_frame`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderStats {
+    pub meshes_submitted: usize,
+    /// Meshes whose bounds are completely outside the view frustum, skipped before any
+    /// per triangle work.
+    pub meshes_culled: usize,
+    /// Meshes drawn with one of their simplified LODs.
+    pub meshes_lod_reduced: usize,
+    /// Meshes tested against the depth pyramid, and how many of them were hidden.
+    pub occlusion_tested: usize,
+    pub occlusion_culled: usize,
+    /// Triangles skipped by a mesh BVH, never counted as submitted.
+    pub bvh_culled: usize,
+    pub triangles_submitted: usize,
+    pub backface_culled: usize,
+    /// Triangles completely behind the near plane.
+    pub near_culled: usize,
+    /// Triangles cut by the near plane, and how many triangles came out of them.
+    pub near_clipped: usize,
+    pub near_clip_generated: usize,
+    /// Same for the screen edges.
+    pub screen_clipped: usize,
+    pub screen_clip_generated: usize,
+    pub triangles_rasterized: usize,
+    pub pixels_shaded: usize,
+    pub depth_rejects: usize,
+
+    /// Transform, lighting, culling, clipping and sorting.
+    pub geometry_time: Duration,
+    pub raster_time: Duration,
+    pub post_time: Duration,
+    pub present_time: Duration,
+}
+
+impl RenderStats {
+    pub fn total_time(&self) -> Duration {
+        self.geometry_time + self.raster_time + self.post_time + self.present_time
+    }
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "meshes: {} ({} culled)", self.meshes_submitted, self.meshes_culled)?;
+        writeln!(f, "lod reduced: {}", self.meshes_lod_reduced)?;
+        writeln!(f, "occluded: {}/{}", self.occlusion_culled, self.occlusion_tested)?;
+        writeln!(f, "bvh culled: {}", self.bvh_culled)?;
+        writeln!(f, "tris submitted: {}", self.triangles_submitted)?;
+        writeln!(f, "backface culled: {}", self.backface_culled)?;
+        writeln!(f, "near culled: {}", self.near_culled)?;
+        writeln!(f, "near clipped: {} (+{})", self.near_clipped, self.near_clip_generated)?;
+        writeln!(f, "screen clipped: {} (+{})", self.screen_clipped, self.screen_clip_generated)?;
+        writeln!(f, "tris rasterized: {}", self.triangles_rasterized)?;
+        writeln!(f, "pixels shaded: {}", self.pixels_shaded)?;
+        writeln!(f, "depth rejects: {}", self.depth_rejects)?;
+        writeln!(f, "geometry: {:.2}ms", self.geometry_time.as_secs_f32() * 1000.0)?;
+        writeln!(f, "raster: {:.2}ms", self.raster_time.as_secs_f32() * 1000.0)?;
+        writeln!(f, "post: {:.2}ms", self.post_time.as_secs_f32() * 1000.0)?;
+        write!(f, "present: {:.2}ms", self.present_time.as_secs_f32() * 1000.0)
+    }
+}
@@ -1,4 +1,12 @@
-use crate::engine::{rendering::texture::Texture, types::vector::{vector2::Vector2, vector3::Vector3}};
+use crate::engine::{
+    rendering::{
+        pixel_buffer::PixelBuffer,
+        shapes::{self, FillRule},
+        sprite::SpriteOptions,
+        texture::Texture,
+    },
+    types::{rect::Rect, vector::vector2::Vector2},
+};
 
 
 pub trait Renderer {
@@ -6,6 +14,10 @@ pub trait Renderer {
     fn clear(&mut self, color: u32);
     fn draw_square(&mut self, a: Vector2<i32>, b: Vector2<i32>, color: u32, filled: bool, fill_color: u32); 
     fn draw_pixel(&mut self, pos: Vector2<i32>, color: u32);
+    /// Reads back a pixel of the framebuffer, `None` outside of it.
+    fn get_pixel(&self, pos: Vector2<i32>) -> Option<u32>;
+    /// Mixes `color` over the current pixel, `alpha` in 0.0..=1.0.
+    fn blend_pixel(&mut self, pos: Vector2<i32>, color: u32, alpha: f32);
     fn draw_line(&mut self, a: Vector2<i32>, b: Vector2<i32>, color: u32);
     fn width(&self) -> usize;
     fn height(&self) -> usize;
@@ -13,4 +25,93 @@ pub trait Renderer {
 
     fn fill_triangle(&mut self, a: Vector2<i32>, b: Vector2<i32>, c: Vector2<i32>, color: u32);
     fn get_x_at_y(&self, p1: Vector2<i32>, p2: Vector2<i32>, y: i32) -> i32;
+
+    /// Draws `text` with its top left corner at `pos`, each font pixel scaled to `scale` x `scale`.
+    fn draw_text(&mut self, pos: Vector2<i32>, text: &str, color: u32, scale: u32);
+
+    /// Draws `texture` with the sprite pivot at `pos`.
+    fn draw_sprite(&mut self, texture: &Texture, pos: Vector2<i32>, options: &SpriteOptions);
+
+    /// Fills the area connected to `pos` whose color is within `tolerance` (0.0..=1.0 per
+    /// channel) of the color at `pos`. Returns how many pixels changed.
+    fn flood_fill(&mut self, pos: Vector2<i32>, color: u32, tolerance: f32) -> usize;
+    fn copy_region(&self, region: Rect) -> PixelBuffer;
+    /// Pastes `source` with its top left corner at `pos`, skipping `transparent` pixels.
+    fn paste_region(&mut self, source: &PixelBuffer, pos: Vector2<i32>, transparent: Option<u32>);
+
+    fn draw_hline(&mut self, x0: i32, x1: i32, y: i32, color: u32) {
+        if y < 0 || y >= self.height() as i32 {
+            return;
+        }
+        // Only walk the part of the span that lands on the framebuffer
+        let start = x0.min(x1).max(0);
+        let end = x0.max(x1).min(self.width() as i32 - 1);
+        for x in start..=end {
+            self.draw_pixel(Vector2::new(x, y), color);
+        }
+    }
+
+    fn draw_circle(&mut self, center: Vector2<i32>, radius: i32, color: u32, filled: bool, fill_color: u32) {
+        if filled {
+            shapes::circle_spans(center, radius, |x0, x1, y| self.draw_hline(x0, x1, y, fill_color));
+        } else {
+            shapes::circle(center, radius, |x, y| self.draw_pixel(Vector2::new(x, y), color));
+        }
+    }
+
+    fn draw_ellipse(
+        &mut self,
+        center: Vector2<i32>,
+        radius: Vector2<i32>,
+        color: u32,
+        filled: bool,
+        fill_color: u32,
+    ) {
+        if filled {
+            shapes::ellipse_spans(center, radius.x, radius.y, |x0, x1, y| {
+                self.draw_hline(x0, x1, y, fill_color)
+            });
+        } else {
+            shapes::ellipse(center, radius.x, radius.y, |x, y| {
+                self.draw_pixel(Vector2::new(x, y), color)
+            });
+        }
+    }
+
+    /// Closed outline through every point.
+    fn draw_polygon(&mut self, points: &[Vector2<i32>], color: u32) {
+        for i in 0..points.len() {
+            self.draw_line(points[i], points[(i + 1) % points.len()], color);
+        }
+    }
+
+    fn fill_polygon(&mut self, points: &[Vector2<i32>], color: u32, rule: FillRule) {
+        shapes::polygon_spans(points, rule, |x0, x1, y| self.draw_hline(x0, x1, y, color));
+    }
+
+    /// Angles in radians, 0 points to +x and they grow clockwise on screen.
+    fn draw_arc(&mut self, center: Vector2<i32>, radius: i32, start: f32, end: f32, color: u32) {
+        self.draw_polyline(&shapes::arc_points(center, radius, start, end), color);
+    }
+
+    fn draw_quadratic_bezier(&mut self, p0: Vector2<i32>, p1: Vector2<i32>, p2: Vector2<i32>, color: u32) {
+        self.draw_polyline(&shapes::quadratic_bezier_points(p0, p1, p2), color);
+    }
+
+    fn draw_cubic_bezier(
+        &mut self,
+        p0: Vector2<i32>,
+        p1: Vector2<i32>,
+        p2: Vector2<i32>,
+        p3: Vector2<i32>,
+        color: u32,
+    ) {
+        self.draw_polyline(&shapes::cubic_bezier_points(p0, p1, p2, p3), color);
+    }
+
+    fn draw_polyline(&mut self, points: &[Vector2<i32>], color: u32) {
+        for segment in points.windows(2) {
+            self.draw_line(segment[0], segment[1], color);
+        }
+    }
 }
@@ -4,14 +4,29 @@ use std::{cell::RefCell, rc::Rc};
 use minifb::Window;
 
 use crate::engine::{
-    rendering::renderer::Renderer, types::vector::vector2::Vector2,
+    rendering::{
+        color,
+        fit_mode::{self, FitMode},
+        font::BitmapFont,
+        pixel_buffer::{self, PixelBuffer},
+        renderer::Renderer,
+        sprite::{self, SpriteOptions},
+        texture::Texture,
+    },
+    types::{rect::Rect, vector::vector2::Vector2},
 };
 
 pub struct Renderer2D {
     buffer: Vec<u32>,
     width: usize,
     height: usize,
-    window: Rc<RefCell<Window>>,
+    /// Window the buffer is presented to, `None` for off-screen targets.
+    window: Option<Rc<RefCell<Window>>>,
+    pub font: BitmapFont,
+    /// How `buffer` is sized and scaled when presented to a window of a different size.
+    pub fit_mode: FitMode,
+    /// Window sized copy of `buffer` when it has to be scaled to be presented.
+    present_buffer: Vec<u32>,
 }
 
 impl Renderer2D {
@@ -20,20 +35,79 @@ impl Renderer2D {
             buffer,
             width,
             height,
-            window,
+            window: Some(window),
+            font: BitmapFont::builtin(),
+            fit_mode: FitMode::Fixed { width, height },
+            present_buffer: vec![],
         }
     }
+
+    /// Renderer that never presents, its buffer is read back instead (e.g. render-to-texture).
+    pub fn offscreen(width: usize, height: usize) -> Self {
+        Self {
+            buffer: vec![0; width * height],
+            width,
+            height,
+            window: None,
+            font: BitmapFont::builtin(),
+            fit_mode: FitMode::Fixed { width, height },
+            present_buffer: vec![],
+        }
+    }
+
+    /// Size of the window in pixels, `None` for off-screen renderers.
+    pub fn window_size(&self) -> Option<(usize, usize)> {
+        self.window.as_ref().map(|window| window.borrow().get_size())
+    }
+
+    /// Reallocates the buffer, its contents are lost.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.buffer = vec![0; width * height];
+    }
+
+    pub fn buffer(&self) -> &[u32] {
+        &self.buffer
+    }
+
+    pub fn buffer_mut(&mut self) -> &mut [u32] {
+        &mut self.buffer
+    }
 }
 
 impl Renderer for Renderer2D {
     
     
     fn render(&mut self, _delta_time: f32) {
-        if let Err(e) = self
-            .window
-            .borrow_mut()
-            .update_with_buffer(&self.buffer, self.width, self.height)
-        {
+        let Some(window) = &self.window else {
+            return;
+        };
+
+        let (window_width, window_height) = window.borrow().get_size();
+        let same_size = (window_width, window_height) == (self.width, self.height);
+        let result = if same_size || window_width == 0 || window_height == 0 {
+            window
+                .borrow_mut()
+                .update_with_buffer(&self.buffer, self.width, self.height)
+        } else {
+            let area = self.fit_mode.placement(self.width, self.height, window_width, window_height);
+            self.present_buffer.resize(window_width * window_height, 0);
+            fit_mode::present_scaled(
+                &self.buffer,
+                self.width,
+                self.height,
+                &mut self.present_buffer,
+                window_width,
+                window_height,
+                area,
+            );
+            window
+                .borrow_mut()
+                .update_with_buffer(&self.present_buffer, window_width, window_height)
+        };
+
+        if let Err(e) = result {
             eprintln!("Error updating window buffer: {:?}", e);
         }
     }
@@ -49,6 +123,21 @@ impl Renderer for Renderer2D {
         }
     }
 
+    fn get_pixel(&self, pos: Vector2<i32>) -> Option<u32> {
+        pixel_buffer::get_pixel(&self.buffer, self.width, self.height, pos)
+    }
+
+    fn blend_pixel(&mut self, pos: Vector2<i32>, color: u32, alpha: f32) {
+        if pos.x >= 0 && pos.y >= 0 && pos.x < self.width as i32 && pos.y < self.height as i32 {
+            let index = pos.y as usize * self.width + pos.x as usize;
+            self.buffer[index] = if alpha >= 1.0 {
+                color
+            } else {
+                color::lerp(self.buffer[index], color, alpha)
+            };
+        }
+    }
+
     fn draw_square(&mut self, a: Vector2<i32>, b: Vector2<i32>, color: u32, filled: bool, fill_color: u32) {
         let min_x = a.x.min(b.x);
         let max_x = a.x.max(b.x);
@@ -146,6 +235,60 @@ impl Renderer for Renderer2D {
         x.round() as i32 // Round to nearest integer
     }
 
+    fn draw_text(&mut self, pos: Vector2<i32>, text: &str, color: u32, scale: u32) {
+        let scale = scale.max(1) as i32;
+        let mut pen = pos;
+
+        for c in text.chars() {
+            if c == '\n' {
+                pen = Vector2::new(pos.x, pen.y + self.font.line_height * scale);
+                continue;
+            }
+            let Some(glyph) = self.font.glyph(c) else {
+                continue;
+            };
+
+            for gy in 0..glyph.height {
+                for gx in 0..glyph.width {
+                    if !glyph.is_set(gx, gy) {
+                        continue;
+                    }
+                    let x = pen.x + (glyph.x_offset + gx as i32) * scale;
+                    let y = pen.y + (glyph.y_offset + gy as i32) * scale;
+                    for sy in 0..scale {
+                        for sx in 0..scale {
+                            // Written directly, `self.draw_pixel` would conflict with the glyph borrow
+                            let (px, py) = (x + sx, y + sy);
+                            if px >= 0 && py >= 0 && px < self.width as i32 && py < self.height as i32 {
+                                self.buffer[py as usize * self.width + px as usize] = color;
+                            }
+                        }
+                    }
+                }
+            }
+            pen.x += glyph.x_advance * scale;
+        }
+    }
+
+    fn draw_sprite(&mut self, texture: &Texture, pos: Vector2<i32>, options: &SpriteOptions) {
+        let screen = Rect::new(0, 0, self.width as i32, self.height as i32);
+        sprite::rasterize(texture, pos, options, screen, |x, y, color, alpha| {
+            self.blend_pixel(Vector2::new(x, y), color, alpha);
+        });
+    }
+
+    fn flood_fill(&mut self, pos: Vector2<i32>, color: u32, tolerance: f32) -> usize {
+        pixel_buffer::flood_fill(&mut self.buffer, self.width, self.height, pos, color, tolerance)
+    }
+
+    fn copy_region(&self, region: Rect) -> PixelBuffer {
+        pixel_buffer::copy_region(&self.buffer, self.width, self.height, region)
+    }
+
+    fn paste_region(&mut self, source: &PixelBuffer, pos: Vector2<i32>, transparent: Option<u32>) {
+        pixel_buffer::paste_region(&mut self.buffer, self.width, self.height, source, pos, transparent);
+    }
+
     // ? https://github.com/ssloy/tinyrenderer/wiki/Lesson-2:-Triangle-rasterization-and-back-face-culling
     fn fill_triangle(&mut self, v1: Vector2<i32>, v2: Vector2<i32>, v3: Vector2<i32>, color: u32) {
         // Make mutable copies to sort
@@ -178,9 +321,9 @@ impl Renderer for Renderer2D {
         for i in 0..total_height as i32 {
             let second_half = i as f32 > b.y - a.y || b.y == a.y;
             let segment_height = if second_half { c.y - b.y } else { b.y - a.y };
-            let alpha = i as f32 / total_height as f32;
+            let alpha = i as f32 / total_height;
             let beta = if segment_height != 0.0 {
-                (i as f32 - (if second_half { b.y - a.y } else { 0.0 })) as f32 / segment_height as f32
+                (i as f32 - (if second_half { b.y - a.y } else { 0.0 })) / segment_height
             } else {
                 0.0
             };
@@ -1,35 +1,265 @@
-use std::{cell::RefCell, mem::swap, rc::Rc, vec};
+use std::{cell::RefCell, collections::HashMap, mem::swap, rc::Rc, time::Instant, vec};
 
 use minifb::Window;
 
 use crate::engine::{
     rendering::{
-        mesh::Mesh, palette::Palette, renderer::Renderer, renderer_2d::Renderer2D, texture::Texture,
+        bsp::BspTree,
+        camera::Camera3D,
+        debug_view::{self, RenderMode},
+        frustum::Frustum,
+        hiz::DepthPyramid,
+        lod::LodCache,
+        mesh::Mesh,
+        palette::Palette,
+        pixel_buffer::PixelBuffer,
+        post_processing::PostProcessing,
+        render_stats::RenderStats,
+        renderer::Renderer,
+        renderer_2d::Renderer2D,
+        sprite::SpriteOptions,
+        texture::Texture,
+        texture_poll::TexturePool,
+        toon::ToonShading,
     },
     types::{
+        bounds::{Aabb, BoundingSphere},
+        bvh::Bvh,
+        rect::Rect,
         triangle::Triangle,
-        vector::{vector_ops::VectorOps, vector2::Vector2, vector3::Vector3, vector4::Vector4},
+        vector::{
+            matrix3x3::Matrix3x3, matrix4x4::Matrix4x4, vector_ops::VectorOps, vector2::Vector2, vector3::Vector3,
+            vector4::Vector4,
+        },
     },
 };
 
+const NEAR_PLANE: f32 = 0.2;
+const NORMAL_LINE_LENGTH: f32 = 0.3;
+
 pub struct Renderer3D {
     pub renderer_2d: Renderer2D,
     pub depth_buffer: Vec<f32>,
+    pub normal_buffer: Vec<Vector3<f32>>,
+    pub post_processing: PostProcessing,
+    pub toon: Option<ToonShading>,
+    pub mode: RenderMode,
+    pub wireframe_color: u32,
+    pub normals_color: u32,
+    /// Writes per pixel this frame, used by `RenderMode::Overdraw`.
+    pub overdraw_buffer: Vec<u32>,
+    /// Area of the framebuffer the 3D scene is mapped to.
+    pub viewport: Rect,
+    /// Optional extra clipping, intersected with the viewport.
+    pub scissor: Option<Rect>,
+    /// Counters of the frame being drawn.
+    pub stats: RenderStats,
+    /// Counters of the last finished frame, for overlays.
+    pub last_stats: RenderStats,
+    /// When false pixels always overwrite, later triangles win whatever their depth.
+    pub depth_test: bool,
+    /// `draw_scene` draws the meshes marked as occluders first and skips the others when
+    /// their bounds are hidden behind them.
+    pub occlusion_culling: bool,
 }
 
 impl Renderer3D {
     pub fn new(buffer: Vec<u32>, width: usize, height: usize, window: Rc<RefCell<Window>>) -> Self {
-        let renderer_2d: Renderer2D = Renderer2D::new(buffer, width, height, window);
+        Self::from_renderer_2d(Renderer2D::new(buffer, width, height, window))
+    }
+
+    /// Renderer without a window, draw into it and read the result with `to_texture`.
+    pub fn offscreen(width: usize, height: usize) -> Self {
+        Self::from_renderer_2d(Renderer2D::offscreen(width, height))
+    }
+
+    fn from_renderer_2d(renderer_2d: Renderer2D) -> Self {
+        let (width, height) = (renderer_2d.width(), renderer_2d.height());
         Self {
             renderer_2d,
             depth_buffer: vec![0.0; width * height],
+            normal_buffer: vec![Vector3::zero(); width * height],
+            post_processing: PostProcessing::new(),
+            toon: None,
+            mode: RenderMode::Solid,
+            wireframe_color: 0xFF0000,
+            normals_color: 0x00FFFF,
+            overdraw_buffer: vec![0; width * height],
+            viewport: Rect::new(0, 0, width as i32, height as i32),
+            scissor: None,
+            stats: RenderStats::default(),
+            last_stats: RenderStats::default(),
+            depth_test: true,
+            occlusion_culling: false,
+        }
+    }
+
+    /// Reallocates the color and depth buffers and resets the viewport to cover them.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.renderer_2d.resize(width, height);
+        self.depth_buffer = vec![0.0; width * height];
+        self.normal_buffer = vec![Vector3::zero(); width * height];
+        self.overdraw_buffer = vec![0; width * height];
+        self.viewport = self.full_viewport();
+        self.scissor = None;
+    }
+
+    /// Resizes the buffers to what the fit mode wants for the current window size.
+    /// Returns true if the resolution changed, so projections need recomputing.
+    pub fn fit_to_window(&mut self) -> bool {
+        let Some((window_width, window_height)) = self.renderer_2d.window_size() else {
+            return false;
+        };
+        // Minimized windows report a zero size, keep the last resolution
+        if window_width == 0 || window_height == 0 {
+            return false;
+        }
+
+        let (width, height) = self.renderer_2d.fit_mode.resolution(window_width, window_height);
+        if (width, height) == (self.width(), self.height()) {
+            return false;
         }
+        self.resize(width, height);
+        true
     }
 
-    pub fn get_shading_color(dp: f32, palette: &'static dyn Palette) -> u32 {
+    /// Copies the current framebuffer into a texture, ready to register in a `TexturePool`.
+    pub fn to_texture(&self, name: &str) -> Texture {
+        Texture::from_buffer(name, self.renderer_2d.buffer(), self.width() as u32, self.height() as u32)
+    }
+
+    /// Like `to_texture`, but overwrites an existing texture in place.
+    pub fn copy_to_texture(&self, texture: &mut Texture) {
+        texture.copy_from_buffer(self.renderer_2d.buffer(), self.width() as u32, self.height() as u32);
+    }
+
+    pub fn get_shading_color(dp: f32, palette: &dyn Palette) -> u32 {
         palette.get_shading_color(dp)
     }
 
+    /// Like `get_shading_color`, but snaps the lighting to bands when toon shading is on.
+    pub fn shade(&self, dp: f32, palette: &dyn Palette) -> u32 {
+        match &self.toon {
+            Some(toon) => palette.get_shading_color(toon.quantize(dp)),
+            None => palette.get_shading_color(dp),
+        }
+    }
+
+    /// Clears the depth related buffers and starts counting a new frame.
+    pub fn begin_frame(&mut self) {
+        self.last_stats = std::mem::take(&mut self.stats);
+        self.clear_depth();
+    }
+
+    /// Viewport covering the whole framebuffer.
+    pub fn full_viewport(&self) -> Rect {
+        Rect::new(0, 0, self.width() as i32, self.height() as i32)
+    }
+
+    /// Region pixels can be written to: viewport, scissor and framebuffer intersected.
+    pub fn clip_rect(&self) -> Rect {
+        let rect = self.viewport.intersect(&self.full_viewport());
+        match &self.scissor {
+            Some(scissor) => rect.intersect(scissor),
+            None => rect,
+        }
+    }
+
+    /// Clears color and depth inside `clip_rect` only, so other viewports are kept.
+    pub fn clear_viewport(&mut self, color: u32) {
+        let rect = self.clip_rect();
+        let width = self.width();
+        for y in rect.y..rect.bottom() {
+            let row = y as usize * width;
+            let span = row + rect.x as usize..row + rect.right() as usize;
+            self.renderer_2d.buffer_mut()[span.clone()].fill(color);
+            self.depth_buffer[span.clone()].fill(0.0);
+            self.normal_buffer[span.clone()].fill(Vector3::zero());
+            self.overdraw_buffer[span].fill(0);
+        }
+    }
+
+    /// Maps normalized device coordinates (-1..1, y up) to pixels inside the viewport.
+    pub fn ndc_to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+        let vp = &self.viewport;
+        (
+            vp.x as f32 + (x + 1.0) * 0.5 * vp.width as f32,
+            vp.y as f32 + (1.0 - y) * 0.5 * vp.height as f32,
+        )
+    }
+
+    pub fn clear_depth(&mut self) {
+        self.depth_buffer.fill(0.0);
+        self.normal_buffer.fill(Vector3::zero());
+        self.overdraw_buffer.fill(0);
+    }
+
+    /// Fills a screen space triangle testing against the depth buffer.
+    /// Values divided by the clip space w interpolate linearly on screen, so each vertex uv
+    /// must be divided by it and hold 1/w in its z, and each vertex `w` must hold view z / w.
+    /// The depth buffer gets 1/z in view space whatever the projection.
+    /// `normal` is written to the normal buffer for the outline pass.
+    /// With a `texture` it is sampled perspective correct instead of using `color`.
+    pub fn fill_triangle_depth(
+        &mut self,
+        tri: &Triangle,
+        normal: Vector3<f32>,
+        color: u32,
+        texture: Option<&Texture>,
+    ) {
+        let (a, b, c) = (tri.v1, tri.v2, tri.v3);
+
+        let edge = |p: Vector4<f32>, q: Vector4<f32>, x: f32, y: f32| -> f32 {
+            (q.x - p.x) * (y - p.y) - (q.y - p.y) * (x - p.x)
+        };
+
+        let area = edge(a, b, c.x, c.y);
+        if area.abs() < f32::EPSILON {
+            return;
+        }
+
+        let width = self.width() as i32;
+        let clip = self.clip_rect();
+        let (low, high) = (a.xy().min(b.xy()).min(c.xy()), a.xy().max(b.xy()).max(c.xy()));
+        let min_x = (low.x.floor() as i32).max(clip.x);
+        let max_x = (high.x.ceil() as i32).min(clip.right() - 1);
+        let min_y = (low.y.floor() as i32).max(clip.y);
+        let max_y = (high.y.ceil() as i32).min(clip.bottom() - 1);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                // Sample at the pixel center
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let w0 = edge(b, c, px, py) / area;
+                let w1 = edge(c, a, px, py) / area;
+                let w2 = 1.0 - w0 - w1;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+
+                let inv_w = w0 * tri.uv[0].z + w1 * tri.uv[1].z + w2 * tri.uv[2].z;
+                let depth = inv_w / (w0 * a.w + w1 * b.w + w2 * c.w);
+                let idx = y as usize * width as usize + x as usize;
+                if !self.depth_test || depth > self.depth_buffer[idx] {
+                    self.stats.pixels_shaded += 1;
+                    self.overdraw_buffer[idx] += 1;
+                    self.depth_buffer[idx] = depth;
+                    self.normal_buffer[idx] = normal;
+                    let color = match texture {
+                        Some(tex) => {
+                            let uv = tri.uv[0] * w0 + tri.uv[1] * w1 + tri.uv[2] * w2;
+                            tex.sample_colour(uv.x / inv_w, uv.y / inv_w)
+                        }
+                        None => color,
+                    };
+                    self.draw_pixel(Vector2::new(x, y), color);
+                } else {
+                    self.stats.depth_rejects += 1;
+                }
+            }
+        }
+    }
+
     // https://github.com/OneLoneCoder/Javidx9/blob/master/ConsoleGameEngine/BiggerProjects/Engine3D/OneLoneCoder_olcEngine3D_Part3.cpp
     pub fn intersect_plane(
         plane_p: Vector4<f32>,    // A known point on the plane
@@ -107,6 +337,17 @@ impl Renderer3D {
             outside_points_tex.push(in_tri.uv[2]);
         }
 
+        // Pieces start at the inside points, which reverses the winding when v2 is the only one
+        // on its side. Swap it back so backface culling still works on clipped triangles
+        let v2_alone = (d1 >= 0.0) != (d0 >= 0.0) && (d1 >= 0.0) != (d2 >= 0.0);
+        let keep_winding = |mut tri: Triangle| {
+            if v2_alone {
+                swap(&mut tri.v2, &mut tri.v3);
+                tri.uv.swap(1, 2);
+            }
+            tri
+        };
+
         // Classify triangle points
         match inside_points.len() {
             0 => {
@@ -124,22 +365,14 @@ impl Renderer3D {
                 let (p2, t2) = Renderer3D::intersect_plane(plane_p, plane_n, p0, outside_points[1]);
 
                 let p0_tex = inside_points_tex[0];
-                let uv1 = Vector3::new(
-                    p0_tex.x + t1 * (outside_points_tex[0].x - p0_tex.x),
-                    p0_tex.y + t1 * (outside_points_tex[0].y - p0_tex.y),
-                    1.0,
-                );
-                let uv2 = Vector3::new(
-                    p0_tex.x + t2 * (outside_points_tex[1].x - p0_tex.x),
-                    p0_tex.y + t2 * (outside_points_tex[1].y - p0_tex.y),
-                    1.0,
-                );
+                let uv1 = p0_tex.lerp(outside_points_tex[0], t1);
+                let uv2 = p0_tex.lerp(outside_points_tex[1], t2);
 
-                vec![
+                vec![keep_winding(
                     Triangle::new(p0, p1, p2)
                         .set_light_color(in_tri.light_color)
                         .set_uv([p0_tex, uv1, uv2]),
-                ]
+                )]
             }
             2 => {
                 // 2 points inside, 1 outside — clip into 2 triangles (a quad)
@@ -150,34 +383,467 @@ impl Renderer3D {
 
                 let p0_tex = inside_points_tex[0];
                 let p1_tex = inside_points_tex[1];
-                let uv0 = Vector3::new(
-                    p0_tex.x + t0 * (outside_points_tex[0].x - p0_tex.x),
-                    p0_tex.y + t0 * (outside_points_tex[0].y - p0_tex.y),
-                    1.0,
-                );
-                let uv1 = Vector3::new(
-                    p1_tex.x + t1 * (outside_points_tex[0].x - p1_tex.x),
-                    p1_tex.y + t1 * (outside_points_tex[0].y - p1_tex.y),
-                    1.0,
-                );
+                let uv0 = p0_tex.lerp(outside_points_tex[0], t0);
+                let uv1 = p1_tex.lerp(outside_points_tex[0], t1);
 
                 vec![
-                    Triangle::new(p0, p1, i0)
-                        .set_light_color(in_tri.light_color)
-                        .set_uv([p0_tex, p1_tex, uv0]),
-                    Triangle::new(p1, i1, i0)
-                        .set_light_color(in_tri.light_color)
-                        .set_uv([p1_tex, uv1, uv0]),
+                    keep_winding(
+                        Triangle::new(p0, p1, i0)
+                            .set_light_color(in_tri.light_color)
+                            .set_uv([p0_tex, p1_tex, uv0]),
+                    ),
+                    keep_winding(
+                        Triangle::new(p1, i1, i0)
+                            .set_light_color(in_tri.light_color)
+                            .set_uv([p1_tex, uv1, uv0]),
+                    ),
                 ]
             }
             _ => vec![], // Should never happen
         }
     }
 
-    pub fn draw_mesh(&mut self, _mesh: Mesh) {
-        todo!()
+    /// Transforms, lights, clips and rasterizes every triangle of `mesh` as seen from `camera`,
+    /// using the camera projection fitted to the viewport.
+    /// `texture` is the mesh texture looked up by the caller, see `Mesh::texture`.
+    /// `lod` is the level this view drew the mesh at last time, the one picked now is returned.
+    pub fn draw_mesh(
+        &mut self,
+        mesh: &Mesh,
+        camera: &Camera3D,
+        palette: &dyn Palette,
+        texture: Option<&Texture>,
+        lod: usize,
+    ) -> usize {
+        let geometry_start = Instant::now();
+
+        // 1-3. Rotate and translate, world matrix
+        let world_matrix = mesh.world_matrix();
+
+        // 4. Camera
+        let camera_matrix = camera.calc_view();
+        let mat_proj = &camera.projection_matrix(self.viewport.width as usize, self.viewport.height as usize);
+        let view_direction = camera.projection.view_direction();
+
+        // Skip the whole mesh when its bounds can't be seen, sphere first as it is cheaper
+        self.stats.meshes_submitted += 1;
+        let frustum = self.frustum(camera);
+        if !frustum.intersects_sphere(&mesh.world_sphere())
+            || !frustum.intersects_aabb(&mesh.world_bounds())
+        {
+            self.stats.meshes_culled += 1;
+            self.stats.geometry_time += geometry_start.elapsed();
+            return lod;
+        }
+
+        let lod = mesh.select_lod(self.screen_size(&mesh.world_sphere(), camera), lod);
+        let tris = mesh.lod_tris(lod);
+        if lod > 0 {
+            self.stats.meshes_lod_reduced += 1;
+        }
+
+        let mut triangles_to_raster: Vec<(Triangle, Vector3<f32>)> = vec![];
+        let mut normal_lines: Vec<(Vector3<f32>, Vector3<f32>)> = vec![];
+
+        let vertex_normals = if self.mode == RenderMode::VertexNormals {
+            Self::vertex_normals(tris, &mesh.normal_matrix())
+        } else {
+            HashMap::new()
+        };
+
+        // With a BVH only triangles in groups inside the frustum go on. The frustum is taken to
+        // object space, where the BVH lives, by adding the world matrix. It only covers `tris`
+        let candidates: Vec<&Triangle> = match mesh.bvh().filter(|_| lod == 0) {
+            Some(bvh) => {
+                let view_proj = Matrix4x4::multiply_matrix(&camera_matrix, mat_proj);
+                let local_frustum = Frustum::from_matrix(&Matrix4x4::multiply_matrix(&world_matrix, &view_proj));
+                let visible = bvh.query(|b| local_frustum.intersects_aabb(b));
+                self.stats.bvh_culled += tris.len() - visible.len();
+                visible.into_iter().map(|i| &tris[i]).collect()
+            }
+            None => tris.iter().collect(),
+        };
+
+        for tri in candidates {
+            self.stats.triangles_submitted += 1;
+
+            let tri_transformed = Triangle::new(
+                Matrix4x4::multiply_vec(&world_matrix, &tri.v1).perspective_divide(),
+                Matrix4x4::multiply_vec(&world_matrix, &tri.v2).perspective_divide(),
+                Matrix4x4::multiply_vec(&world_matrix, &tri.v3).perspective_divide(),
+            )
+            .set_uv(tri.uv);
+
+            let Some((normal, projected)) =
+                self.project_triangle(&tri_transformed, &camera_matrix, mat_proj, view_direction, palette)
+            else {
+                continue;
+            };
+            triangles_to_raster.extend(projected.into_iter().map(|t| (t, normal)));
+
+            match self.mode {
+                RenderMode::FaceNormals => {
+                    let center = (tri_transformed.v1 + tri_transformed.v2 + tri_transformed.v3)
+                        .to_vector3()
+                        / 3.0;
+                    normal_lines.push((center, center + normal * NORMAL_LINE_LENGTH));
+                }
+                RenderMode::VertexNormals => {
+                    for (v, world) in [tri.v1, tri.v2, tri.v3].iter().zip([
+                        tri_transformed.v1,
+                        tri_transformed.v2,
+                        tri_transformed.v3,
+                    ]) {
+                        if let Some(n) = vertex_normals.get(&Self::vertex_key(v)) {
+                            let start = world.to_vector3();
+                            normal_lines.push((start, start + *n * NORMAL_LINE_LENGTH));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        //  Sort back to front
+        triangles_to_raster.sort_by(|(t1, _), (t2, _)| {
+            let z1 = (t1.v1.z + t1.v2.z + t1.v3.z) / 3.0;
+            let z2 = (t2.v1.z + t2.v2.z + t2.v3.z) / 3.0;
+            z1.partial_cmp(&z2)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .reverse() // Back to front (descending order)
+        });
+
+        self.stats.geometry_time += geometry_start.elapsed();
+        let raster_start = Instant::now();
+
+        // Loop through all transformed, viewed, projected, and sorted triangles
+        for (tri_to_raster, normal) in triangles_to_raster {
+            self.raster_triangle(tri_to_raster, normal, texture);
+        }
+
+        for (start, end) in normal_lines {
+            let a = self.project_point(start, &camera_matrix, mat_proj);
+            let b = self.project_point(end, &camera_matrix, mat_proj);
+            let clip = self.clip_rect();
+            if let (Some(a), Some(b)) = (a, b)
+                && clip.contains(a.x, a.y)
+                && clip.contains(b.x, b.y)
+            {
+                self.draw_line(a, b, self.normals_color);
+            }
+        }
+
+        self.stats.raster_time += raster_start.elapsed();
+        lod
     }
 
+    /// Culls, lights, near clips and projects a world space triangle keeping its uvs.
+    /// Returns its world normal and the screen space triangles, `None` if it faces away.
+    fn project_triangle(
+        &mut self,
+        tri: &Triangle,
+        camera_matrix: &Matrix4x4,
+        mat_proj: &Matrix4x4,
+        view_direction: Option<Vector3<f32>>,
+        palette: &dyn Palette,
+    ) -> Option<(Vector3<f32>, Vec<Triangle>)> {
+        // Calc Normal
+        let l1 = tri.v2 - tri.v1;
+        let l2 = tri.v3 - tri.v1;
+        let normal = l1.cross3(l2).normalize(); // You normally need to normalize a normal
+
+        let viewed_triangle = Triangle::new(
+            Matrix4x4::multiply_vec(camera_matrix, &tri.v1).perspective_divide(),
+            Matrix4x4::multiply_vec(camera_matrix, &tri.v2).perspective_divide(),
+            Matrix4x4::multiply_vec(camera_matrix, &tri.v3).perspective_divide(),
+        )
+        .set_uv(tri.uv);
+
+        // Get ray from camera to triangle in view space, parallel projections share one direction
+        let view_normal = (viewed_triangle.v2 - viewed_triangle.v1).cross3(viewed_triangle.v3 - viewed_triangle.v1);
+        let v_camera_ray = view_direction.unwrap_or(viewed_triangle.v1.to_vector3());
+
+        // If ray is aligned with normal, make it visible.
+        if view_normal.dot(v_camera_ray) >= 0.0 {
+            self.stats.backface_culled += 1;
+            return None;
+        }
+
+        let light_direction = Vector3::new(0.0, 1.0, -1.0).normalize();
+        let dp = light_direction.dot(normal); // How "aligned" are light direction and triangle sureface normal?
+
+        let colour: u32 = self.shade(dp, palette);
+        let viewed_triangle = viewed_triangle.set_light_color(colour);
+
+        // Clip viewed triangle againts near plane, this could form two aditional triangles.
+        let clipped = Renderer3D::triangle_clip_against_plane(
+            Vector4::new(0.0, 0.0, NEAR_PLANE, 1.0),
+            Vector4::new(0.0, 0.0, 1.0, 1.0),
+            &viewed_triangle,
+        );
+
+        match clipped.as_slice() {
+            [] => self.stats.near_culled += 1,
+            [tc] if *tc == viewed_triangle => {}
+            _ => {
+                self.stats.near_clipped += 1;
+                self.stats.near_clip_generated += clipped.len();
+            }
+        }
+
+        let mut projected_tris = vec![];
+        for tc in clipped {
+            let clip = [tc.v1, tc.v2, tc.v3].map(|v| Matrix4x4::multiply_vec(mat_proj, &v));
+            let mut projected = Triangle::new(
+                clip[0].perspective_divide(),
+                clip[1].perspective_divide(),
+                clip[2].perspective_divide(),
+            )
+            .set_light_color(tc.light_color);
+
+            // Divide by the clip w whatever interpolates on screen, see `fill_triangle_depth`.
+            // It is view z for perspective and 1 for parallel projections.
+            let view_z = [tc.v1.z, tc.v2.z, tc.v3.z];
+            for (i, v) in [&mut projected.v1, &mut projected.v2, &mut projected.v3].into_iter().enumerate() {
+                let inv_w = 1.0 / clip[i].w;
+                projected.uv[i] = (tc.uv[i].xy() * inv_w).extend(inv_w);
+                v.w = view_z[i] * inv_w;
+            }
+
+            // Convertir a coordenadas de pantalla
+            for v in [&mut projected.v1, &mut projected.v2, &mut projected.v3] {
+                (v.x, v.y) = self.ndc_to_screen(v.x, v.y);
+            }
+
+            projected_tris.push(projected);
+        }
+        Some((normal, projected_tris))
+    }
+
+    /// Clips a projected triangle to `clip_rect` and fills or outlines it per the render mode.
+    fn raster_triangle(&mut self, tri: Triangle, normal: Vector3<f32>, texture: Option<&Texture>) {
+        let clipped = self.clip_to_screen(tri.clone());
+        if clipped.len() != 1 || clipped[0] != tri {
+            self.stats.screen_clipped += 1;
+            self.stats.screen_clip_generated += clipped.len();
+        }
+
+        for t in clipped {
+            self.stats.triangles_rasterized += 1;
+            if self.mode.fills() {
+                self.fill_triangle_depth(&t, normal, t.light_color, texture);
+            }
+
+            if self.mode.draws_wireframe() {
+                self.draw_triangle(t.v1.into(), t.v2.into(), t.v3.into(), self.wireframe_color);
+            }
+        }
+    }
+
+    /// Paints `bsp` farthest first with the painter's algorithm, the depth buffer is written
+    /// but not tested. Textures are looked up in `textures` like in `draw_scene`.
+    pub fn draw_bsp(&mut self, bsp: &BspTree, camera: &Camera3D, palette: &dyn Palette, textures: &TexturePool) {
+        let geometry_start = Instant::now();
+        let camera_matrix = camera.calc_view();
+        let mat_proj = &camera.projection_matrix(self.viewport.width as usize, self.viewport.height as usize);
+        let view_direction = camera.projection.view_direction();
+
+        // Parallel projections look along one direction, taken back to world space
+        let world_direction =
+            view_direction.map(|d| camera.right() * d.x + camera.up() * d.y + camera.forward() * d.z);
+
+        let mut triangles_to_raster = vec![];
+        for polygon in bsp.back_to_front(camera.position, world_direction) {
+            self.stats.triangles_submitted += 1;
+            if let Some((normal, projected)) =
+                self.project_triangle(&polygon.tri, &camera_matrix, mat_proj, view_direction, palette)
+            {
+                let texture = polygon.texture.as_deref().and_then(|name| textures.get(name));
+                triangles_to_raster.extend(projected.into_iter().map(|t| (t, normal, texture)));
+            }
+        }
+
+        self.stats.geometry_time += geometry_start.elapsed();
+        let raster_start = Instant::now();
+
+        let depth_test = std::mem::replace(&mut self.depth_test, false);
+        for (tri, normal, texture) in triangles_to_raster {
+            self.raster_triangle(tri, normal, texture);
+        }
+        self.depth_test = depth_test;
+
+        self.stats.raster_time += raster_start.elapsed();
+    }
+
+    /// Height in pixels of the sphere projected with `camera`, as used to pick mesh LODs.
+    pub fn screen_size(&self, sphere: &BoundingSphere, camera: &Camera3D) -> f32 {
+        let mat_proj = camera.projection_matrix(self.viewport.width as usize, self.viewport.height as usize);
+        let viewed = Matrix4x4::multiply_vec(&camera.calc_view(), &Vector4::from_vector3(sphere.center, 1.0));
+
+        // Clip w is view z for perspective and 1 for parallel projections
+        let w = viewed.z * mat_proj.m[2][3] + mat_proj.m[3][3];
+        if w <= 0.0 {
+            return f32::INFINITY;
+        }
+        sphere.radius * mat_proj.m[1][1] / w * self.viewport.height as f32
+    }
+
+    /// World space frustum of `camera` with its projection fitted to the viewport.
+    pub fn frustum(&self, camera: &Camera3D) -> Frustum {
+        let mat_proj = camera.projection_matrix(self.viewport.width as usize, self.viewport.height as usize);
+        Frustum::from_matrix(&Matrix4x4::multiply_matrix(&camera.calc_view(), &mat_proj))
+    }
+
+    /// Draws `meshes` looking up their textures in `textures`. With a `scene_bvh` built over
+    /// the meshes world bounds, groups of meshes outside the view are skipped at once.
+    /// With `occlusion_culling`, see `Mesh::occluder`. `lods` keeps the mesh levels of this
+    /// view between frames.
+    pub fn draw_scene(
+        &mut self,
+        meshes: &[Mesh],
+        scene_bvh: Option<&Bvh>,
+        camera: &Camera3D,
+        palette: &dyn Palette,
+        textures: &TexturePool,
+        lods: &mut LodCache,
+    ) {
+        let visible = match scene_bvh {
+            Some(bvh) => {
+                let frustum = self.frustum(camera);
+                let visible = bvh.query(|b| frustum.intersects_aabb(b));
+                self.stats.meshes_submitted += meshes.len() - visible.len();
+                self.stats.meshes_culled += meshes.len() - visible.len();
+                visible
+            }
+            None => (0..meshes.len()).collect(),
+        };
+
+        if !self.occlusion_culling {
+            for i in visible {
+                let mesh = &meshes[i];
+                let texture = mesh.texture.as_deref().and_then(|name| textures.get(name));
+                lods.set(i, self.draw_mesh(mesh, camera, palette, texture, lods.get(i)));
+            }
+            return;
+        }
+
+        let (occluders, occludees): (Vec<usize>, Vec<usize>) = visible.into_iter().partition(|&i| meshes[i].occluder);
+        for i in occluders {
+            let mesh = &meshes[i];
+            let texture = mesh.texture.as_deref().and_then(|name| textures.get(name));
+            lods.set(i, self.draw_mesh(mesh, camera, palette, texture, lods.get(i)));
+        }
+
+        let pyramid = self.depth_pyramid();
+        for i in occludees {
+            let mesh = &meshes[i];
+            self.stats.occlusion_tested += 1;
+            if self.is_occluded(&pyramid, &mesh.world_bounds(), camera) {
+                self.stats.meshes_submitted += 1;
+                self.stats.occlusion_culled += 1;
+                continue;
+            }
+            let texture = mesh.texture.as_deref().and_then(|name| textures.get(name));
+            lods.set(i, self.draw_mesh(mesh, camera, palette, texture, lods.get(i)));
+        }
+    }
+
+    /// Hierarchical Z of what has been drawn so far, for `is_occluded`.
+    pub fn depth_pyramid(&self) -> DepthPyramid {
+        DepthPyramid::build(&self.depth_buffer, self.width(), self.height())
+    }
+
+    /// True if the world space box `bounds` is completely hidden behind what was drawn into
+    /// `pyramid` from `camera`. Boxes crossing the near plane are never occluded.
+    pub fn is_occluded(&self, pyramid: &DepthPyramid, bounds: &Aabb, camera: &Camera3D) -> bool {
+        if bounds.is_empty() {
+            return false;
+        }
+        let view = camera.calc_view();
+        let mat_proj = camera.projection_matrix(self.viewport.width as usize, self.viewport.height as usize);
+
+        // Screen rectangle and closest depth of the box corners
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+        let mut nearest = 0.0_f32;
+        for corner in bounds.corners() {
+            let viewed = Matrix4x4::multiply_vec(&view, &Vector4::from_vector3(corner, 1.0));
+            if viewed.z < NEAR_PLANE {
+                return false;
+            }
+            nearest = nearest.max(1.0 / viewed.z);
+
+            let projected = Matrix4x4::multiply_vec(&mat_proj, &viewed).perspective_divide();
+            let (x, y) = self.ndc_to_screen(projected.x, projected.y);
+            (min_x, min_y) = (min_x.min(x), min_y.min(y));
+            (max_x, max_y) = (max_x.max(x), max_y.max(y));
+        }
+
+        let (x, y) = (min_x.floor() as i32, min_y.floor() as i32);
+        let area = Rect::new(x, y, max_x.ceil() as i32 - x + 1, max_y.ceil() as i32 - y + 1);
+        pyramid.is_occluded(&area.intersect(&self.clip_rect()), nearest)
+    }
+
+    /// Clips a screen space triangle against the edges of `clip_rect`.
+    pub fn clip_to_screen(&self, tri: Triangle) -> Vec<Triangle> {
+        let clip = self.clip_rect();
+        let (left, top) = (clip.x as f32, clip.y as f32);
+        let (right, bottom) = ((clip.right() - 1) as f32, (clip.bottom() - 1) as f32);
+        let planes = [
+            (Vector4::new(0.0, top, 0.0, 1.0), Vector4::new(0.0, 1.0, 0.0, 1.0)),
+            (Vector4::new(0.0, bottom, 0.0, 1.0), Vector4::new(0.0, -1.0, 0.0, 1.0)),
+            (Vector4::new(left, 0.0, 0.0, 1.0), Vector4::new(1.0, 0.0, 0.0, 1.0)),
+            (Vector4::new(right, 0.0, 0.0, 1.0), Vector4::new(-1.0, 0.0, 0.0, 1.0)),
+        ];
+
+        let mut tri_queue: Vec<Triangle> = vec![tri];
+        for (plane_p, plane_n) in planes {
+            let mut new_triangles: Vec<Triangle> = vec![];
+            for test in tri_queue.drain(..) {
+                new_triangles.extend(Renderer3D::triangle_clip_against_plane(plane_p, plane_n, &test));
+            }
+            tri_queue = new_triangles;
+        }
+        tri_queue
+    }
+
+    /// Projects a world space point to the screen, `None` if it is behind the near plane.
+    pub fn project_point(
+        &self,
+        point: Vector3<f32>,
+        view: &Matrix4x4,
+        mat_proj: &Matrix4x4,
+    ) -> Option<Vector2<i32>> {
+        let viewed = Matrix4x4::multiply_vec(view, &Vector4::from_vector3(point, 1.0));
+        if viewed.z < NEAR_PLANE {
+            return None;
+        }
+        let projected = Matrix4x4::multiply_vec(mat_proj, &viewed).perspective_divide();
+        let (x, y) = self.ndc_to_screen(projected.x, projected.y);
+        Some(Vector2::new(x as i32, y as i32))
+    }
+
+    /// Averages the normals of the faces sharing each vertex position, then takes them to
+    /// world space with `normal_matrix`.
+    fn vertex_normals(tris: &[Triangle], normal_matrix: &Matrix3x3) -> HashMap<[u32; 3], Vector3<f32>> {
+        let mut normals: HashMap<[u32; 3], Vector3<f32>> = HashMap::new();
+        for tri in tris {
+            let normal = (tri.v2 - tri.v1).cross3(tri.v3 - tri.v1).normalize();
+            for v in [&tri.v1, &tri.v2, &tri.v3] {
+                let n = normals.entry(Self::vertex_key(v)).or_insert(Vector3::zero());
+                *n += normal;
+            }
+        }
+        for n in normals.values_mut() {
+            *n = normal_matrix.multiply_vec(n.normalize()).normalize();
+        }
+        normals
+    }
+
+    fn vertex_key(v: &Vector4<f32>) -> [u32; 3] {
+        [v.x.to_bits(), v.y.to_bits(), v.z.to_bits()]
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn textured_triangle(
         &mut self,
         p1: Vector2<i32>,
@@ -375,10 +1041,49 @@ impl Renderer3D {
             }
         }
     }
+
+    /// Applies the debug views, toon outlines and post-processing to the finished 3D image.
+    /// Anything drawn afterwards (HUD, text) is left untouched by them.
+    pub fn end_frame(&mut self) {
+        let post_start = Instant::now();
+        let (width, height) = (self.width(), self.height());
+        match self.mode {
+            RenderMode::Depth => debug_view::draw_depth(self.renderer_2d.buffer_mut(), &self.depth_buffer),
+            RenderMode::Overdraw => {
+                debug_view::draw_overdraw(self.renderer_2d.buffer_mut(), &self.overdraw_buffer)
+            }
+            _ => {}
+        }
+
+        let shaded = !matches!(
+            self.mode,
+            RenderMode::Wireframe | RenderMode::Depth | RenderMode::Overdraw
+        );
+        if let Some(toon) = self.toon.filter(|t| t.outline && shaded) {
+            toon.draw_outlines(
+                self.renderer_2d.buffer_mut(),
+                &self.depth_buffer,
+                &self.normal_buffer,
+                width,
+                height,
+            );
+        }
+        self.post_processing
+            .apply(self.renderer_2d.buffer_mut(), &self.depth_buffer, width, height);
+        self.stats.post_time += post_start.elapsed();
+    }
+
+    /// Shows the buffer in the window as is.
+    pub fn present(&mut self, delta_time: f32) {
+        let present_start = Instant::now();
+        self.renderer_2d.render(delta_time);
+        self.stats.present_time += present_start.elapsed();
+    }
 }
 impl Renderer for Renderer3D {
     fn render(&mut self, delta_time: f32) {
-        self.renderer_2d.render(delta_time);
+        self.end_frame();
+        self.present(delta_time);
     }
 
     fn clear(&mut self, color: u32) {
@@ -389,6 +1094,14 @@ impl Renderer for Renderer3D {
         self.renderer_2d.draw_pixel(pos, color);
     }
 
+    fn get_pixel(&self, pos: Vector2<i32>) -> Option<u32> {
+        self.renderer_2d.get_pixel(pos)
+    }
+
+    fn blend_pixel(&mut self, pos: Vector2<i32>, color: u32, alpha: f32) {
+        self.renderer_2d.blend_pixel(pos, color, alpha);
+    }
+
     fn draw_square(
         &mut self,
         a: Vector2<i32>,
@@ -423,4 +1136,24 @@ impl Renderer for Renderer3D {
     fn get_x_at_y(&self, p1: Vector2<i32>, p2: Vector2<i32>, y: i32) -> i32 {
         self.renderer_2d.get_x_at_y(p1, p2, y)
     }
+
+    fn draw_text(&mut self, pos: Vector2<i32>, text: &str, color: u32, scale: u32) {
+        self.renderer_2d.draw_text(pos, text, color, scale)
+    }
+
+    fn draw_sprite(&mut self, texture: &Texture, pos: Vector2<i32>, options: &SpriteOptions) {
+        self.renderer_2d.draw_sprite(texture, pos, options)
+    }
+
+    fn flood_fill(&mut self, pos: Vector2<i32>, color: u32, tolerance: f32) -> usize {
+        self.renderer_2d.flood_fill(pos, color, tolerance)
+    }
+
+    fn copy_region(&self, region: Rect) -> PixelBuffer {
+        self.renderer_2d.copy_region(region)
+    }
+
+    fn paste_region(&mut self, source: &PixelBuffer, pos: Vector2<i32>, transparent: Option<u32>) {
+        self.renderer_2d.paste_region(source, pos, transparent)
+    }
 }
@@ -0,0 +1,246 @@
+// Rasterization of the 2D primitives offered by `Renderer`. Every function reports pixels
+// (or horizontal spans) through a callback so any renderer can reuse them.
+
+use crate::engine::types::vector::vector2::Vector2;
+
+/// Decides which parts of a self intersecting polygon are inside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillRule {
+    /// Inside if a ray crosses an odd number of edges.
+    #[default]
+    EvenOdd,
+    /// Inside if the edges crossed don't cancel out by direction.
+    NonZero,
+}
+
+// ? https://en.wikipedia.org/wiki/Midpoint_circle_algorithm
+pub fn circle(center: Vector2<i32>, radius: i32, mut plot: impl FnMut(i32, i32)) {
+    if radius <= 0 {
+        plot(center.x, center.y);
+        return;
+    }
+
+    let (mut x, mut y) = (radius, 0);
+    let mut err = 1 - radius;
+    while x >= y {
+        for (dx, dy) in [(x, y), (y, x), (-y, x), (-x, y), (-x, -y), (-y, -x), (y, -x), (x, -y)] {
+            plot(center.x + dx, center.y + dy);
+        }
+        y += 1;
+        if err < 0 {
+            err += 2 * y + 1;
+        } else {
+            x -= 1;
+            err += 2 * (y - x) + 1;
+        }
+    }
+}
+
+/// Filled circle as horizontal spans `(x_start, x_end, y)`, both ends included.
+pub fn circle_spans(center: Vector2<i32>, radius: i32, mut span: impl FnMut(i32, i32, i32)) {
+    let radius = radius.max(0);
+
+    // Widest x reached on each row, taken from the outline so both always match
+    let mut widths = vec![0; radius as usize + 1];
+    circle(Vector2::new(0, 0), radius, |x, y| {
+        if y >= 0 {
+            let w = &mut widths[y as usize];
+            *w = (*w).max(x);
+        }
+    });
+
+    for (y, &w) in widths.iter().enumerate() {
+        let y = y as i32;
+        span(center.x - w, center.x + w, center.y + y);
+        if y != 0 {
+            span(center.x - w, center.x + w, center.y - y);
+        }
+    }
+}
+
+// ? https://zingl.github.io/bresenham.html (midpoint ellipse, two regions)
+fn ellipse_quadrant(rx: i32, ry: i32, mut point: impl FnMut(i32, i32)) {
+    // A zero radius flattens the ellipse into a line (or a point), which the midpoint
+    // regions below can't step through
+    if ry == 0 {
+        (0..=rx).for_each(|x| point(x, 0));
+        return;
+    }
+    if rx == 0 {
+        (0..=ry).for_each(|y| point(0, y));
+        return;
+    }
+
+    let (rx2, ry2) = ((rx as i64) * (rx as i64), (ry as i64) * (ry as i64));
+    let (mut x, mut y) = (0i64, ry as i64);
+
+    // Region 1: slope > -1
+    let mut d1 = ry2 - rx2 * ry as i64 + rx2 / 4;
+    while ry2 * x <= rx2 * y {
+        point(x as i32, y as i32);
+        if d1 < 0 {
+            x += 1;
+            d1 += ry2 * (2 * x + 1);
+        } else {
+            x += 1;
+            y -= 1;
+            d1 += ry2 * (2 * x + 1) - 2 * rx2 * y;
+        }
+    }
+
+    // Region 2: slope < -1
+    let mut d2 = ry2 * (2 * x + 1) * (2 * x + 1) / 4 + rx2 * (y - 1) * (y - 1) - rx2 * ry2;
+    while y >= 0 {
+        point(x as i32, y as i32);
+        if d2 > 0 {
+            y -= 1;
+            d2 += rx2 * (1 - 2 * y);
+        } else {
+            x += 1;
+            y -= 1;
+            d2 += ry2 * 2 * x + rx2 * (1 - 2 * y);
+        }
+    }
+}
+
+pub fn ellipse(center: Vector2<i32>, rx: i32, ry: i32, mut plot: impl FnMut(i32, i32)) {
+    let (rx, ry) = (rx.abs(), ry.abs());
+    ellipse_quadrant(rx, ry, |x, y| {
+        plot(center.x + x, center.y + y);
+        plot(center.x - x, center.y + y);
+        plot(center.x + x, center.y - y);
+        plot(center.x - x, center.y - y);
+    });
+}
+
+/// Filled ellipse as horizontal spans `(x_start, x_end, y)`, both ends included.
+pub fn ellipse_spans(center: Vector2<i32>, rx: i32, ry: i32, mut span: impl FnMut(i32, i32, i32)) {
+    let (rx, ry) = (rx.abs(), ry.abs());
+
+    // Widest x reached on each row of the quadrant
+    let mut widths = vec![-1; ry as usize + 1];
+    ellipse_quadrant(rx, ry, |x, y| {
+        let w = &mut widths[y as usize];
+        *w = (*w).max(x);
+    });
+
+    for (y, &w) in widths.iter().enumerate() {
+        if w < 0 {
+            continue;
+        }
+        let y = y as i32;
+        span(center.x - w, center.x + w, center.y + y);
+        if y != 0 {
+            span(center.x - w, center.x + w, center.y - y);
+        }
+    }
+}
+
+/// Scanline polygon fill, sampling at pixel centers. Spans are `(x_start, x_end, y)`.
+pub fn polygon_spans(points: &[Vector2<i32>], rule: FillRule, mut span: impl FnMut(i32, i32, i32)) {
+    if points.len() < 3 {
+        return;
+    }
+
+    let min_y = points.iter().map(|p| p.y).min().unwrap_or(0);
+    let max_y = points.iter().map(|p| p.y).max().unwrap_or(0);
+    let mut crossings: Vec<(f32, i32)> = vec![];
+
+    for y in min_y..max_y {
+        let sample_y = y as f32 + 0.5;
+        crossings.clear();
+
+        for i in 0..points.len() {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            let (ay, by) = (a.y as f32, b.y as f32);
+            // Half open test so shared vertices are only counted once
+            if (ay <= sample_y) != (by <= sample_y) {
+                let t = (sample_y - ay) / (by - ay);
+                let x = a.x as f32 + t * (b.x - a.x) as f32;
+                crossings.push((x, if b.y > a.y { 1 } else { -1 }));
+            }
+        }
+        crossings.sort_by(|c1, c2| c1.0.partial_cmp(&c2.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            let (x0, dir) = pair[0];
+            let x1 = pair[1].0;
+            winding += dir;
+
+            let inside = match rule {
+                FillRule::EvenOdd => winding % 2 != 0,
+                FillRule::NonZero => winding != 0,
+            };
+            if inside {
+                // Pixels whose center lies between both crossings
+                let start = (x0 - 0.5).ceil() as i32;
+                let end = (x1 - 0.5).ceil() as i32 - 1;
+                if start <= end {
+                    span(start, end, y);
+                }
+            }
+        }
+    }
+}
+
+/// Points along an arc, angles in radians starting at +x and growing clockwise on screen.
+pub fn arc_points(center: Vector2<i32>, radius: i32, start: f32, end: f32) -> Vec<Vector2<i32>> {
+    let sweep = end - start;
+    // About one segment per pixel of arc length
+    let steps = ((sweep.abs() * radius as f32).ceil() as usize).max(1);
+
+    (0..=steps)
+        .map(|i| {
+            let angle = start + sweep * i as f32 / steps as f32;
+            Vector2::new(
+                center.x + (angle.cos() * radius as f32).round() as i32,
+                center.y + (angle.sin() * radius as f32).round() as i32,
+            )
+        })
+        .collect()
+}
+
+// Rough curve length from its control polygon, used to pick how many segments to draw
+fn control_length(points: &[Vector2<f32>]) -> f32 {
+    points
+        .windows(2)
+        .map(|w| {
+            let d = w[1] - w[0];
+            (d.x * d.x + d.y * d.y).sqrt()
+        })
+        .sum()
+}
+
+pub fn quadratic_bezier_points(p0: Vector2<i32>, p1: Vector2<i32>, p2: Vector2<i32>) -> Vec<Vector2<i32>> {
+    let [a, b, c] = [p0, p1, p2].map(|p| p.cast::<f32>().unwrap());
+    let steps = ((control_length(&[a, b, c]) / 4.0).ceil() as usize).max(1);
+
+    (0..=steps)
+        .map(|i| {
+            let t = i as f32 / steps as f32;
+            let u = 1.0 - t;
+            let p = a * (u * u) + b * (2.0 * u * t) + c * (t * t);
+            Vector2::new(p.x.round() as i32, p.y.round() as i32)
+        })
+        .collect()
+}
+
+pub fn cubic_bezier_points(
+    p0: Vector2<i32>,
+    p1: Vector2<i32>,
+    p2: Vector2<i32>,
+    p3: Vector2<i32>,
+) -> Vec<Vector2<i32>> {
+    let [a, b, c, d] = [p0, p1, p2, p3].map(|p| p.cast::<f32>().unwrap());
+    let steps = ((control_length(&[a, b, c, d]) / 4.0).ceil() as usize).max(1);
+
+    (0..=steps)
+        .map(|i| {
+            let t = i as f32 / steps as f32;
+            let u = 1.0 - t;
+            let p = a * (u * u * u) + b * (3.0 * u * u * t) + c * (3.0 * u * t * t) + d * (t * t * t);
+            Vector2::new(p.x.round() as i32, p.y.round() as i32)
+        })
+        .collect()
+}
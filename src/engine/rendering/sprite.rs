@@ -0,0 +1,199 @@
+use crate::engine::{
+    rendering::{color, texture::Texture},
+    types::{rect::Rect, vector::vector2::Vector2},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Filter {
+    #[default]
+    Nearest,
+    Bilinear,
+}
+
+/// How a texture is drawn by `Renderer::draw_sprite`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteOptions {
+    /// Part of the texture to draw, the whole texture if `None`.
+    pub source: Option<Rect>,
+    pub scale: Vector2<f32>,
+    /// Clockwise, in radians.
+    pub rotation: f32,
+    /// Point of the sprite placed at the draw position and rotated around,
+    /// from (0, 0) top left to (1, 1) bottom right.
+    pub pivot: Vector2<f32>,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// Multiplied with every texel, white leaves it unchanged.
+    pub tint: u32,
+    /// Global opacity, multiplied with the texture alpha.
+    pub opacity: f32,
+    pub filter: Filter,
+}
+
+impl Default for SpriteOptions {
+    fn default() -> Self {
+        Self {
+            source: None,
+            scale: Vector2::new(1.0, 1.0),
+            rotation: 0.0,
+            pivot: Vector2::new(0.0, 0.0),
+            flip_x: false,
+            flip_y: false,
+            tint: 0xFFFFFF,
+            opacity: 1.0,
+            filter: Filter::Nearest,
+        }
+    }
+}
+
+impl SpriteOptions {
+    pub fn set_source(mut self, source: Rect) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn set_scale(mut self, x: f32, y: f32) -> Self {
+        self.scale = Vector2::new(x, y);
+        self
+    }
+
+    pub fn set_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn set_pivot(mut self, x: f32, y: f32) -> Self {
+        self.pivot = Vector2::new(x, y);
+        self
+    }
+
+    pub fn set_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn set_tint(mut self, tint: u32) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn set_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn set_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// Screen pixels covered by a sprite, and for each one the texel to sample.
+/// Works backwards from the destination so rotated and scaled sprites have no holes.
+pub fn rasterize(
+    texture: &Texture,
+    pos: Vector2<i32>,
+    options: &SpriteOptions,
+    clip: Rect,
+    mut plot: impl FnMut(i32, i32, u32, f32),
+) {
+    let source = options
+        .source
+        .unwrap_or(Rect::new(0, 0, texture.width as i32, texture.height as i32))
+        .intersect(&Rect::new(0, 0, texture.width as i32, texture.height as i32));
+    if source.is_empty() || options.scale.x == 0.0 || options.scale.y == 0.0 {
+        return;
+    }
+
+    let size = Vector2::new(
+        source.width as f32 * options.scale.x.abs(),
+        source.height as f32 * options.scale.y.abs(),
+    );
+    let pivot = Vector2::new(options.pivot.x * size.x, options.pivot.y * size.y);
+    let (sin, cos) = options.rotation.sin_cos();
+
+    // Bounding box of the rotated corners
+    let corners = [
+        Vector2::new(0.0, 0.0),
+        Vector2::new(size.x, 0.0),
+        Vector2::new(0.0, size.y),
+        Vector2::new(size.x, size.y),
+    ]
+    .map(|c| {
+        let d = c - pivot;
+        Vector2::new(d.x * cos - d.y * sin, d.x * sin + d.y * cos)
+    });
+    let min_x = corners.iter().fold(f32::MAX, |m, c| m.min(c.x)).floor() as i32 + pos.x;
+    let max_x = corners.iter().fold(f32::MIN, |m, c| m.max(c.x)).ceil() as i32 + pos.x;
+    let min_y = corners.iter().fold(f32::MAX, |m, c| m.min(c.y)).floor() as i32 + pos.y;
+    let max_y = corners.iter().fold(f32::MIN, |m, c| m.max(c.y)).ceil() as i32 + pos.y;
+    let area = Rect::new(min_x, min_y, max_x - min_x + 1, max_y - min_y + 1).intersect(&clip);
+
+    for y in area.y..area.bottom() {
+        for x in area.x..area.right() {
+            // Undo the rotation around the pivot, sampling at the pixel center
+            let d = Vector2::new(x as f32 + 0.5 - pos.x as f32, y as f32 + 0.5 - pos.y as f32);
+            let local = Vector2::new(d.x * cos + d.y * sin, -d.x * sin + d.y * cos) + pivot;
+            if local.x < 0.0 || local.y < 0.0 || local.x >= size.x || local.y >= size.y {
+                continue;
+            }
+
+            let mut u = local.x / size.x;
+            let mut v = local.y / size.y;
+            if options.flip_x != (options.scale.x < 0.0) {
+                u = 1.0 - u;
+            }
+            if options.flip_y != (options.scale.y < 0.0) {
+                v = 1.0 - v;
+            }
+
+            let tx = source.x as f32 + u * source.width as f32;
+            let ty = source.y as f32 + v * source.height as f32;
+            let (texel, alpha) = match options.filter {
+                Filter::Nearest => sample_nearest(texture, tx, ty, &source),
+                Filter::Bilinear => sample_bilinear(texture, tx, ty, &source),
+            };
+
+            let alpha = alpha * options.opacity;
+            if alpha > 0.0 {
+                plot(x, y, color::multiply(texel, options.tint), alpha.min(1.0));
+            }
+        }
+    }
+}
+
+fn texel(texture: &Texture, x: i32, y: i32, source: &Rect) -> (u32, f32) {
+    let x = x.clamp(source.x, source.right() - 1) as u32;
+    let y = y.clamp(source.y, source.bottom() - 1) as u32;
+    match texture.get_pixel(x, y) {
+        Some(px) => (color::rgb(px[0], px[1], px[2]), px[3] as f32 / 255.0),
+        None => (0, 0.0),
+    }
+}
+
+fn sample_nearest(texture: &Texture, x: f32, y: f32, source: &Rect) -> (u32, f32) {
+    texel(texture, x.floor() as i32, y.floor() as i32, source)
+}
+
+// Texel centers sit at +0.5, edges are clamped to the source rectangle
+fn sample_bilinear(texture: &Texture, x: f32, y: f32, source: &Rect) -> (u32, f32) {
+    let (x, y) = (x - 0.5, y - 0.5);
+    let (x0, y0) = (x.floor() as i32, y.floor() as i32);
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+    let (c00, a00) = texel(texture, x0, y0, source);
+    let (c10, a10) = texel(texture, x0 + 1, y0, source);
+    let (c01, a01) = texel(texture, x0, y0 + 1, source);
+    let (c11, a11) = texel(texture, x0 + 1, y0 + 1, source);
+
+    let top = color::lerp(c00, c10, fx);
+    let bottom = color::lerp(c01, c11, fx);
+    let alpha_top = a00 + (a10 - a00) * fx;
+    let alpha_bottom = a01 + (a11 - a01) * fx;
+
+    (
+        color::lerp(top, bottom, fy),
+        alpha_top + (alpha_bottom - alpha_top) * fy,
+    )
+}
@@ -9,6 +9,8 @@ pub enum ImageFormat {
     PNG,
     JPG,
     BMP,
+    /// Generated at runtime, not loaded from a file.
+    RAW,
 }
 
 #[derive(Debug)]
@@ -57,7 +59,7 @@ impl Texture {
         let extension = binding
             .extension()
             .and_then(|ext| ext.to_str())
-            .ok_or_else(|| ParseImageFormatError)?;
+            .ok_or(ParseImageFormatError)?;
 
         let format = ImageFormat::from_str(extension)?;
 
@@ -70,6 +72,28 @@ impl Texture {
         })
     }
 
+    /// Builds an opaque texture from 0x00RRGGBB pixels, e.g. a rendered framebuffer.
+    pub fn from_buffer(name: &str, buffer: &[u32], width: u32, height: u32) -> Texture {
+        let mut texture = Texture {
+            path: PathBuf::from(name),
+            format: ImageFormat::RAW,
+            data: Vec::with_capacity(buffer.len() * 4),
+            width,
+            height,
+        };
+        texture.copy_from_buffer(buffer, width, height);
+        texture
+    }
+
+    /// Replaces the pixels like `from_buffer`, reusing the allocation so it can run every frame.
+    pub fn copy_from_buffer(&mut self, buffer: &[u32], width: u32, height: u32) {
+        self.data.clear();
+        self.data
+            .extend(buffer.iter().flat_map(|&c| [(c >> 16) as u8, (c >> 8) as u8, c as u8, 255]));
+        self.width = width;
+        self.height = height;
+    }
+
     pub fn get_pixel(&self, x: u32, y: u32) -> Option<Rgba<u8>> {
         if x >= self.width || y >= self.height {
             return None;
@@ -6,6 +6,12 @@ pub struct TexturePool {
     textures: HashMap<String, Texture>,
 }
 
+impl Default for TexturePool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TexturePool {
     pub fn new() -> Self {
         Self {
@@ -31,6 +37,10 @@ impl TexturePool {
         self.textures.get(name)
     }
 
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Texture> {
+        self.textures.get_mut(name)
+    }
+
     pub fn contains(&self, name: &str) -> bool {
         self.textures.contains_key(name)
     }
@@ -0,0 +1,112 @@
+use crate::engine::types::vector::{vector3::Vector3, vector_ops::VectorOps};
+
+/// Cel shading settings: lighting is snapped to `bands` levels before picking the palette
+/// color, and ink outlines are drawn where depth or normals change abruptly on screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToonShading {
+    pub bands: usize,
+    pub outline: bool,
+    pub outline_color: u32,
+    /// Outline width in pixels.
+    pub outline_thickness: i32,
+    /// Relative view distance change between neighbours that counts as an edge.
+    pub depth_threshold: f32,
+    /// Minimum dot product between neighbour normals before it counts as a crease.
+    pub normal_threshold: f32,
+}
+
+impl Default for ToonShading {
+    fn default() -> Self {
+        Self {
+            bands: 4,
+            outline: true,
+            outline_color: 0x000000,
+            outline_thickness: 1,
+            depth_threshold: 0.1,
+            normal_threshold: 0.8,
+        }
+    }
+}
+
+impl ToonShading {
+    /// Snaps `dp` (the light/normal dot product) to the center of its band.
+    pub fn quantize(&self, dp: f32) -> f32 {
+        if self.bands <= 1 {
+            return 1.0;
+        }
+        let dp = dp.clamp(0.0, 1.0);
+        let band = ((dp * self.bands as f32) as usize).min(self.bands - 1);
+        band as f32 / (self.bands - 1) as f32
+    }
+
+    /// Screen space edge detection over the depth and normal buffers.
+    /// The depth buffer stores 1/z, so 0 means background.
+    pub fn draw_outlines(
+        &self,
+        buffer: &mut [u32],
+        depth_buffer: &[f32],
+        normal_buffer: &[Vector3<f32>],
+        width: usize,
+        height: usize,
+    ) {
+        let is_edge = |a: usize, b: usize| -> bool {
+            let (da, db) = (depth_buffer[a], depth_buffer[b]);
+            if da <= 0.0 && db <= 0.0 {
+                return false;
+            }
+            // Silhouette against the background
+            if da <= 0.0 || db <= 0.0 {
+                return true;
+            }
+
+            let (za, zb) = (1.0 / da, 1.0 / db);
+            if (za - zb).abs() / za.min(zb) > self.depth_threshold {
+                return true;
+            }
+
+            normal_buffer[a].dot(normal_buffer[b]) < self.normal_threshold
+        };
+
+        let mut edges = vec![false; width * height];
+
+        for y in 0..height {
+            for x in 0..width {
+                let idx = y * width + x;
+                for neighbour in [(x + 1 < width).then_some(idx + 1), (y + 1 < height).then_some(idx + width)]
+                    .into_iter()
+                    .flatten()
+                {
+                    if is_edge(idx, neighbour) {
+                        // Ink goes on the closest pixel so silhouettes stay on the object
+                        let closest = if depth_buffer[idx] >= depth_buffer[neighbour] { idx } else { neighbour };
+                        edges[closest] = true;
+                    }
+                }
+            }
+        }
+
+        // Grow the lines to the requested thickness
+        let radius = (self.outline_thickness.max(1) - 1) as usize;
+        if radius > 0 {
+            let thin = edges.clone();
+            for y in 0..height {
+                for x in 0..width {
+                    if !thin[y * width + x] {
+                        continue;
+                    }
+                    for ny in y.saturating_sub(radius)..=(y + radius).min(height - 1) {
+                        for nx in x.saturating_sub(radius)..=(x + radius).min(width - 1) {
+                            edges[ny * width + nx] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        for (pixel, edge) in buffer.iter_mut().zip(edges) {
+            if edge {
+                *pixel = self.outline_color;
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::engine::{
+    rendering::mesh::Mesh,
+    types::{
+        object3d::Object3D,
+        vector::{matrix4x4::Matrix4x4, vector3::Vector3, vector4::Vector4},
+    },
+};
+
+/// Index of a node in its `SceneGraph`, stable for the life of the graph.
+pub type NodeId = usize;
+
+pub struct SceneNode {
+    pub name: String,
+    // Relative to the parent, or to the world for roots
+    local: Object3D,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    // Index in `SceneGraph::meshes`
+    mesh: Option<usize>,
+    // Cached by `SceneGraph::update`
+    world: Matrix4x4,
+    dirty: bool,
+}
+
+/// Tree of transforms: moving a node moves all its children with it, like a turret on a
+/// tank. Meshes are kept in one slice so they can go straight to `Renderer3D::draw_scene`,
+/// each gets the world matrix of its node as `Mesh::parent`.
+#[derive(Default)]
+pub struct SceneGraph {
+    nodes: Vec<SceneNode>,
+    meshes: Vec<Mesh>,
+}
+
+impl SceneGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node under `parent`, or as a root. The mesh `obj` is kept as an offset inside
+    /// the node, leave it at zero to place the mesh with `local` alone.
+    pub fn add(&mut self, name: &str, local: Object3D, mesh: Option<Mesh>, parent: Option<NodeId>) -> NodeId {
+        let id = self.nodes.len();
+        let mesh = mesh.map(|mesh| {
+            self.meshes.push(mesh);
+            self.meshes.len() - 1
+        });
+        self.nodes.push(SceneNode {
+            name: String::from(name),
+            local,
+            parent,
+            children: vec![],
+            mesh,
+            world: Matrix4x4::identity(),
+            dirty: true,
+        });
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(id);
+        }
+        id
+    }
+
+    /// Moves `id` under another node keeping its local transform, so it jumps to follow
+    /// its new parent. False, and nothing changes, if that would make a cycle.
+    pub fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) -> bool {
+        let mut ancestor = parent;
+        while let Some(a) = ancestor {
+            if a == id {
+                return false;
+            }
+            ancestor = self.nodes[a].parent;
+        }
+
+        if let Some(old) = self.nodes[id].parent {
+            self.nodes[old].children.retain(|&c| c != id);
+        }
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(id);
+        }
+        self.nodes[id].parent = parent;
+        self.nodes[id].dirty = true;
+        true
+    }
+
+    /// Recomputes the world matrices of moved nodes and their descendants, call it once a
+    /// frame after changing transforms and before drawing. True if any node moved.
+    pub fn update(&mut self) -> bool {
+        let mut stack: Vec<(NodeId, Matrix4x4, bool)> = (0..self.nodes.len())
+            .filter(|&id| self.nodes[id].parent.is_none())
+            .map(|id| (id, Matrix4x4::identity(), false))
+            .collect();
+        let mut any_moved = false;
+
+        while let Some((id, parent_world, parent_moved)) = stack.pop() {
+            let node = &mut self.nodes[id];
+            let moved = parent_moved || node.dirty;
+            if moved {
+                node.world = Matrix4x4::multiply_matrix(&node.local.world_matrix(), &parent_world);
+                node.dirty = false;
+                any_moved = true;
+                if let Some(mesh) = node.mesh {
+                    self.meshes[mesh].parent = node.world;
+                }
+            }
+            stack.extend(node.children.iter().map(|&child| (child, node.world, moved)));
+        }
+        any_moved
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, id: NodeId) -> &SceneNode {
+        &self.nodes[id]
+    }
+
+    pub fn find(&self, name: &str) -> Option<NodeId> {
+        self.nodes.iter().position(|node| node.name == name)
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.nodes[id].children
+    }
+
+    pub fn local(&self, id: NodeId) -> &Object3D {
+        &self.nodes[id].local
+    }
+
+    /// Marks the node as moved, its world matrix is refreshed on the next `update`.
+    pub fn local_mut(&mut self, id: NodeId) -> &mut Object3D {
+        self.nodes[id].dirty = true;
+        &mut self.nodes[id].local
+    }
+
+    /// As of the last `update`.
+    pub fn world_matrix(&self, id: NodeId) -> &Matrix4x4 {
+        &self.nodes[id].world
+    }
+
+    pub fn world_position(&self, id: NodeId) -> Vector3<f32> {
+        Matrix4x4::multiply_vec(&self.nodes[id].world, &Vector4::new(0.0, 0.0, 0.0, 1.0)).to_vector3()
+    }
+
+    pub fn mesh(&self, id: NodeId) -> Option<&Mesh> {
+        self.nodes[id].mesh.map(|m| &self.meshes[m])
+    }
+
+    /// Marks the node as moved too, as editing `Mesh::obj` or `tris` changes what `update`
+    /// reports.
+    pub fn mesh_mut(&mut self, id: NodeId) -> Option<&mut Mesh> {
+        self.nodes[id].dirty = true;
+        self.nodes[id].mesh.map(|m| &mut self.meshes[m])
+    }
+
+    /// Every mesh in the graph, in the order nodes were added.
+    pub fn meshes(&self) -> &[Mesh] {
+        &self.meshes
+    }
+}
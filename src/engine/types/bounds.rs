@@ -0,0 +1,99 @@
+use crate::engine::types::vector::{
+    matrix4x4::Matrix4x4, vector3::Vector3, vector4::Vector4, vector_ops::VectorOps,
+};
+
+/// Axis aligned bounding box. Empty boxes have `min` above `max`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+}
+
+impl Aabb {
+    pub fn new(min: Vector3<f32>, max: Vector3<f32>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            min: Vector3::new(f32::MAX, f32::MAX, f32::MAX),
+            max: Vector3::new(f32::MIN, f32::MIN, f32::MIN),
+        }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = Vector3<f32>>) -> Self {
+        points.into_iter().fold(Self::empty(), |aabb, p| aabb.grow(p))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    /// Smallest box containing this one and `point`.
+    pub fn grow(&self, point: Vector3<f32>) -> Self {
+        Self {
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+
+    pub fn union(&self, other: &Aabb) -> Self {
+        if other.is_empty() {
+            return *self;
+        }
+        self.grow(other.min).grow(other.max)
+    }
+
+    pub fn center(&self) -> Vector3<f32> {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Half the size on each axis.
+    pub fn extents(&self) -> Vector3<f32> {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn corners(&self) -> [Vector3<f32>; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Vector3::new(a.x, a.y, a.z),
+            Vector3::new(b.x, a.y, a.z),
+            Vector3::new(a.x, b.y, a.z),
+            Vector3::new(b.x, b.y, a.z),
+            Vector3::new(a.x, a.y, b.z),
+            Vector3::new(b.x, a.y, b.z),
+            Vector3::new(a.x, b.y, b.z),
+            Vector3::new(b.x, b.y, b.z),
+        ]
+    }
+
+    /// Box around the transformed corners, it may grow when rotated.
+    pub fn transform(&self, matrix: &Matrix4x4) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        Self::from_points(
+            self.corners()
+                .map(|c| Matrix4x4::multiply_vec(matrix, &Vector4::from_vector3(c, 1.0)).to_vector3()),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vector3<f32>,
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    pub fn new(center: Vector3<f32>, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Sphere centered on the box of `points`, reaching the farthest one.
+    pub fn from_points(points: &[Vector3<f32>]) -> Self {
+        let center = Aabb::from_points(points.iter().copied()).center();
+        let radius = points.iter().map(|p| p.distance(center)).fold(0.0, f32::max);
+        Self { center, radius }
+    }
+}
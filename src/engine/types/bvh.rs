@@ -0,0 +1,157 @@
+use crate::engine::types::{bounds::Aabb, ray::Ray};
+
+/// Items per leaf before a node is split.
+const MAX_LEAF_SIZE: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BvhNode {
+    pub bounds: Aabb,
+    /// Leaves: start of their items in `Bvh::items`. Inner nodes: index of the left child,
+    /// the right one follows it.
+    pub first: usize,
+    /// Number of items, 0 for inner nodes.
+    pub count: usize,
+}
+
+impl BvhNode {
+    pub fn is_leaf(&self) -> bool {
+        self.count > 0
+    }
+}
+
+/// Bounding volume hierarchy over anything with a box: triangles of a mesh, meshes of a
+/// scene... Items are referred to by their index in the slice given to `build`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bvh {
+    /// Root first, children always after their parent.
+    pub nodes: Vec<BvhNode>,
+    /// Item indices grouped by leaf.
+    pub items: Vec<usize>,
+}
+
+impl Bvh {
+    /// Splits at the median of the widest axis of the item centers.
+    pub fn build(bounds: &[Aabb]) -> Self {
+        let mut bvh = Self {
+            nodes: vec![],
+            items: (0..bounds.len()).collect(),
+        };
+        if !bounds.is_empty() {
+            bvh.nodes.push(BvhNode { bounds: Aabb::empty(), first: 0, count: bounds.len() });
+            bvh.subdivide(0, bounds);
+        }
+        bvh
+    }
+
+    fn subdivide(&mut self, node: usize, bounds: &[Aabb]) {
+        let BvhNode { first, count, .. } = self.nodes[node];
+        let items = &mut self.items[first..first + count];
+        self.nodes[node].bounds = items.iter().fold(Aabb::empty(), |b, &i| b.union(&bounds[i]));
+        if count <= MAX_LEAF_SIZE {
+            return;
+        }
+
+        let centers = Aabb::from_points(items.iter().map(|&i| bounds[i].center()));
+        let size = centers.max - centers.min;
+        let axis = |b: &Aabb| {
+            let c = b.center();
+            if size.x >= size.y && size.x >= size.z {
+                c.x
+            } else if size.y >= size.z {
+                c.y
+            } else {
+                c.z
+            }
+        };
+        let mid = count / 2;
+        items.select_nth_unstable_by(mid, |&a, &b| {
+            axis(&bounds[a]).partial_cmp(&axis(&bounds[b])).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let left = self.nodes.len();
+        self.nodes.push(BvhNode { bounds: Aabb::empty(), first, count: mid });
+        self.nodes.push(BvhNode { bounds: Aabb::empty(), first: first + mid, count: count - mid });
+        self.nodes[node] = BvhNode { bounds: self.nodes[node].bounds, first: left, count: 0 };
+
+        self.subdivide(left, bounds);
+        self.subdivide(left + 1, bounds);
+    }
+
+    /// Updates the node boxes for items that moved, keeping the tree shape. Cheaper than
+    /// `build` but the tree gets worse the further items move from where they were built.
+    pub fn refit(&mut self, bounds: &[Aabb]) {
+        for i in (0..self.nodes.len()).rev() {
+            let node = self.nodes[i];
+            self.nodes[i].bounds = if node.is_leaf() {
+                self.items[node.first..node.first + node.count]
+                    .iter()
+                    .fold(Aabb::empty(), |b, &item| b.union(&bounds[item]))
+            } else {
+                self.nodes[node.first].bounds.union(&self.nodes[node.first + 1].bounds)
+            };
+        }
+    }
+
+    /// Items in leaves whose boxes pass `test`, e.g. a frustum or overlap check. Whole
+    /// subtrees are skipped when their box fails.
+    pub fn query(&self, mut test: impl FnMut(&Aabb) -> bool) -> Vec<usize> {
+        let mut found = vec![];
+        let mut stack = vec![0];
+        while let Some(i) = stack.pop() {
+            let Some(node) = self.nodes.get(i) else {
+                continue;
+            };
+            if !test(&node.bounds) {
+                continue;
+            }
+            if node.is_leaf() {
+                found.extend_from_slice(&self.items[node.first..node.first + node.count]);
+            } else {
+                stack.push(node.first);
+                stack.push(node.first + 1);
+            }
+        }
+        found
+    }
+
+    /// Items whose box overlaps `aabb`, for collision queries.
+    pub fn overlapping(&self, aabb: &Aabb) -> Vec<usize> {
+        self.query(|b| {
+            b.min.x <= aabb.max.x
+                && b.max.x >= aabb.min.x
+                && b.min.y <= aabb.max.y
+                && b.max.y >= aabb.min.y
+                && b.min.z <= aabb.max.z
+                && b.max.z >= aabb.min.z
+        })
+    }
+
+    /// Closest hit as `(item, distance)`. `hit` does the exact test against one item.
+    pub fn raycast(&self, ray: &Ray, mut hit: impl FnMut(usize) -> Option<f32>) -> Option<(usize, f32)> {
+        let mut closest: Option<(usize, f32)> = None;
+        let mut stack = vec![0];
+        while let Some(i) = stack.pop() {
+            let Some(node) = self.nodes.get(i) else {
+                continue;
+            };
+            match ray.intersect_aabb(&node.bounds) {
+                Some(t) if closest.is_none_or(|(_, best)| t < best) => {}
+                _ => continue,
+            }
+
+            if node.is_leaf() {
+                for &item in &self.items[node.first..node.first + node.count] {
+                    if let Some(t) = hit(item)
+                        && closest.is_none_or(|(_, best)| t < best)
+                    {
+                        closest = Some((item, t));
+                    }
+                }
+            } else {
+                stack.push(node.first);
+                stack.push(node.first + 1);
+            }
+        }
+        closest
+    }
+}
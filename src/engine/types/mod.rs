@@ -1,3 +1,7 @@
 pub mod vector;
 pub mod triangle;
 pub mod object3d;
+pub mod rect;
+pub mod bounds;
+pub mod ray;
+pub mod bvh;
@@ -1,24 +1,48 @@
-use crate::engine::types::vector::{matrix4x4::Matrix4x4, vector3::Vector3, vector_ops::VectorOps};
+use crate::engine::types::vector::{
+    matrix3x3::Matrix3x3, matrix4x4::Matrix4x4, quaternion::Quaternion, vector3::Vector3, vector_ops::VectorOps,
+};
 
 
 pub struct Object3D {
     pub position: Vector3<f32>,
     pub rotation: Vector3<f32>, // pitch (X), yaw (Y), roll (Z)
+    pub scale: Vector3<f32>,    // Per axis, 1 keeps the original size
+    /// Used instead of `rotation` when set, free of gimbal lock and easy to interpolate.
+    pub orientation: Option<Quaternion>,
 }
 
 impl Object3D {
 
     pub fn new(position: Vector3<f32>, rotation: Vector3<f32>) -> Self {
-        Self { position, rotation }
+        Self { position, rotation, scale: Vector3::new(1.0, 1.0, 1.0), orientation: None }
     }
 
     pub fn zero() -> Self {
         Self {
-            position: Vector3::zero(), rotation: Vector3::zero()
+            position: Vector3::zero(), rotation: Vector3::zero(), scale: Vector3::new(1.0, 1.0, 1.0), orientation: None
         }
     }
 
+    pub fn set_scale(mut self, scale: Vector3<f32>) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn set_orientation(mut self, orientation: Quaternion) -> Self {
+        self.orientation = Some(orientation);
+        self
+    }
+
+    /// `orientation`, or `rotation` converted.
+    pub fn rotation_quaternion(&self) -> Quaternion {
+        self.orientation.unwrap_or_else(|| Quaternion::from_euler(self.rotation))
+    }
+
     pub fn rotation_matrix(&self) -> Matrix4x4 {
+        if let Some(orientation) = self.orientation {
+            return orientation.to_matrix();
+        }
+
         let (rx, ry, rz) = (
             self.rotation.x,
             self.rotation.y,
@@ -33,10 +57,15 @@ impl Object3D {
         Matrix4x4::multiply_matrix(&rot_y, &Matrix4x4::multiply_matrix(&rot_x, &rot_z))
     }
 
-    pub fn transform_matrix(&self) -> Matrix4x4 {
-        let trans = Matrix4x4::translation(self.position);
-        let rot = self.rotation_matrix();
-        Matrix4x4::multiply_matrix(&rot, &trans)
+    /// Local to parent space: scale, then rotation, then translation.
+    pub fn world_matrix(&self) -> Matrix4x4 {
+        let scale_rot = Matrix4x4::multiply_matrix(&Matrix4x4::scale(self.scale), &self.rotation_matrix());
+        Matrix4x4::multiply_matrix(&scale_rot, &Matrix4x4::translation(self.position))
+    }
+
+    /// Transforms normals to parent space, see `Matrix4x4::normal_matrix`.
+    pub fn normal_matrix(&self) -> Matrix3x3 {
+        self.world_matrix().normal_matrix()
     }
-    
-}
\ No newline at end of file
+
+}
@@ -0,0 +1,77 @@
+use crate::engine::types::{
+    bounds::Aabb,
+    vector::{matrix4x4::Matrix4x4, vector3::Vector3, vector4::Vector4, vector_ops::VectorOps},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vector3<f32>,
+    /// Not required to be normalized, hit distances are in multiples of it.
+    pub direction: Vector3<f32>,
+}
+
+impl Ray {
+    pub fn new(origin: Vector3<f32>, direction: Vector3<f32>) -> Self {
+        Self { origin, direction }
+    }
+
+    pub fn at(&self, t: f32) -> Vector3<f32> {
+        self.origin + self.direction * t
+    }
+
+    /// Transforms origin as a point and direction as a vector.
+    pub fn transform(&self, matrix: &Matrix4x4) -> Self {
+        Self {
+            origin: Matrix4x4::multiply_vec(matrix, &Vector4::from_vector3(self.origin, 1.0)).to_vector3(),
+            direction: Matrix4x4::multiply_vec(matrix, &Vector4::from_vector3(self.direction, 0.0)).to_vector3(),
+        }
+    }
+
+    // ? https://tavianator.com/2011/ray_box.html
+    /// Distance where the ray enters the box (0 if it starts inside), `None` if it misses.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut t_min = 0.0_f32;
+        let mut t_max = f32::INFINITY;
+
+        for (origin, direction, min, max) in [
+            (self.origin.x, self.direction.x, aabb.min.x, aabb.max.x),
+            (self.origin.y, self.direction.y, aabb.min.y, aabb.max.y),
+            (self.origin.z, self.direction.z, aabb.min.z, aabb.max.z),
+        ] {
+            let inv = 1.0 / direction;
+            let (t1, t2) = ((min - origin) * inv, (max - origin) * inv);
+            t_min = t_min.max(t1.min(t2));
+            t_max = t_max.min(t1.max(t2));
+        }
+
+        (t_min <= t_max).then_some(t_min)
+    }
+
+    // ? https://en.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm
+    /// Distance to the triangle, both faces count. `None` if it misses or is behind.
+    pub fn intersect_triangle(&self, v1: Vector3<f32>, v2: Vector3<f32>, v3: Vector3<f32>) -> Option<f32> {
+        let edge1 = v2 - v1;
+        let edge2 = v3 - v1;
+        let p = self.direction.cross(edge2);
+        let det = edge1.dot(p);
+        if det.abs() < f32::EPSILON {
+            return None; // Parallel
+        }
+
+        let inv_det = 1.0 / det;
+        let s = self.origin - v1;
+        let u = s.dot(p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = s.cross(edge1);
+        let v = self.direction.dot(q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(q) * inv_det;
+        (t >= 0.0).then_some(t)
+    }
+}
@@ -0,0 +1,173 @@
+use std::ops::Mul;
+
+use crate::engine::types::vector::{
+    matrix4x4::{Matrix4x4, SingularMatrixError},
+    vector3::Vector3,
+};
+
+/// Rotation and scale without translation, mostly to transform normals. Row vectors like
+/// `Matrix4x4`, `a * b` applies `a` first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix3x3 {
+    pub m: [[f32; 3]; 3],
+}
+
+impl Matrix3x3 {
+    pub fn identity() -> Self {
+        Self { m: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]] }
+    }
+
+    /// Upper left block of `matrix`, dropping translation and projection.
+    pub fn from_matrix4(matrix: &Matrix4x4) -> Self {
+        let m = &matrix.m;
+        Self {
+            m: [
+                [m[0][0], m[0][1], m[0][2]],
+                [m[1][0], m[1][1], m[1][2]],
+                [m[2][0], m[2][1], m[2][2]],
+            ],
+        }
+    }
+
+    /// Back to a 4x4 matrix with no translation.
+    pub fn to_matrix4(&self) -> Matrix4x4 {
+        let mut matrix = Matrix4x4::identity();
+        for (row, src) in matrix.m.iter_mut().zip(self.m) {
+            row[..3].copy_from_slice(&src);
+        }
+        matrix
+    }
+
+    pub fn transpose(&self) -> Self {
+        let m = &self.m;
+        Self {
+            m: [
+                [m[0][0], m[1][0], m[2][0]],
+                [m[0][1], m[1][1], m[2][1]],
+                [m[0][2], m[1][2], m[2][2]],
+            ],
+        }
+    }
+
+    /// Cofactor of each element, the inverse transpose scaled by the determinant.
+    pub fn cofactors(&self) -> Self {
+        let a = &self.m;
+        let mut cof = [[0.0; 3]; 3];
+        for (i, row) in cof.iter_mut().enumerate() {
+            for (j, c) in row.iter_mut().enumerate() {
+                let (i1, i2, j1, j2) = ((i + 1) % 3, (i + 2) % 3, (j + 1) % 3, (j + 2) % 3);
+                *c = a[i1][j1] * a[i2][j2] - a[i1][j2] * a[i2][j1];
+            }
+        }
+        Self { m: cof }
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let cof = self.cofactors();
+        (0..3).map(|j| self.m[0][j] * cof.m[0][j]).sum()
+    }
+
+    pub fn inverse(&self) -> Result<Self, SingularMatrixError> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return Err(SingularMatrixError);
+        }
+        Ok(self.cofactors().transpose() * (1.0 / det))
+    }
+
+    /// Normal matrix of the transform this matrix applies: inverse transpose, so normals
+    /// stay perpendicular to surfaces under non-uniform scale. Normalize the results.
+    pub fn normal_matrix(&self) -> Self {
+        // A flattened matrix has no inverse, the cofactors alone still give the directions
+        self.inverse().map(|inv| inv.transpose()).unwrap_or_else(|_| self.cofactors())
+    }
+
+    /// Largest factor any vector gets scaled by (spectral norm). For scale and rotation it is
+    /// the largest scale, and it stays exact under shear.
+    pub fn max_stretch(&self) -> f32 {
+        // Largest eigenvalue of the symmetric `self * transpose`, in closed form
+        // ? https://en.wikipedia.org/wiki/Eigenvalue_algorithm#3%C3%973_matrices
+        let a = (*self * self.transpose()).m;
+        let off = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+        if off == 0.0 {
+            return a[0][0].max(a[1][1]).max(a[2][2]).max(0.0).sqrt();
+        }
+
+        let q = (a[0][0] + a[1][1] + a[2][2]) / 3.0;
+        let p = (((a[0][0] - q).powi(2) + (a[1][1] - q).powi(2) + (a[2][2] - q).powi(2) + 2.0 * off)
+            / 6.0)
+            .sqrt();
+        let mut b = Self { m: a };
+        for (i, row) in b.m.iter_mut().enumerate() {
+            row[i] -= q;
+            row.iter_mut().for_each(|v| *v /= p);
+        }
+        let phi = (b.determinant() / 2.0).clamp(-1.0, 1.0).acos() / 3.0;
+        (q + 2.0 * p * phi.cos()).max(0.0).sqrt()
+    }
+
+    pub fn multiply_vec(&self, v: Vector3<f32>) -> Vector3<f32> {
+        let m = &self.m;
+        Vector3::new(
+            v.x * m[0][0] + v.y * m[1][0] + v.z * m[2][0],
+            v.x * m[0][1] + v.y * m[1][1] + v.z * m[2][1],
+            v.x * m[0][2] + v.y * m[1][2] + v.z * m[2][2],
+        )
+    }
+}
+
+impl Mul for Matrix3x3 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let mut result = [[0.0; 3]; 3];
+        for (r, row) in result.iter_mut().enumerate() {
+            for (c, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| self.m[r][k] * other.m[k][c]).sum();
+            }
+        }
+        Self { m: result }
+    }
+}
+
+impl Mul<f32> for Matrix3x3 {
+    type Output = Self;
+
+    fn mul(self, factor: f32) -> Self::Output {
+        Self { m: self.m.map(|row| row.map(|v| v * factor)) }
+    }
+}
+
+impl Mul<Matrix3x3> for Vector3<f32> {
+    type Output = Vector3<f32>;
+
+    fn mul(self, matrix: Matrix3x3) -> Self::Output {
+        matrix.multiply_vec(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use super::*;
+
+    fn stretch(matrix: Matrix4x4) -> f32 {
+        Matrix3x3::from_matrix4(&matrix).max_stretch()
+    }
+
+    #[test]
+    fn max_stretch_is_the_largest_scale() {
+        assert!((stretch(Matrix4x4::identity()) - 1.0).abs() < 1e-5);
+
+        let scale = Matrix4x4::scale(Vector3::new(1.0, 3.0, 2.0));
+        let trs = scale * Matrix4x4::rotation_y(0.7) * Matrix4x4::translation(Vector3::new(4.0, 5.0, 6.0));
+        assert!((stretch(trs) - 3.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn max_stretch_covers_rotation_under_non_uniform_scale() {
+        let sheared = Matrix4x4::rotation_z(FRAC_PI_4) * Matrix4x4::scale(Vector3::new(2.0, 1.0, 1.0));
+        assert!((stretch(sheared) - 2.0).abs() < 1e-4);
+    }
+}
@@ -1,7 +1,31 @@
-use std::f32::consts::PI;
+use core::fmt;
+use std::{error::Error, f32::consts::PI, ops::Mul};
 
-use crate::engine::types::vector::{vector3::Vector3, vector4::Vector4, vector_ops::VectorOps};
+use crate::engine::types::vector::{
+    matrix3x3::Matrix3x3, vector3::Vector3, vector4::Vector4, vector_ops::VectorOps,
+};
 
+/// Returned when inverting a matrix whose determinant is 0.
+#[derive(Debug)]
+pub struct SingularMatrixError;
+
+impl fmt::Display for SingularMatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "matrix is singular and has no inverse")
+    }
+}
+
+impl Error for SingularMatrixError {}
+
+/// Which way view space z points. The engine is left handed: x right, y up, z forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handedness {
+    Left,
+    Right,
+}
+
+/// Row vectors: points are transformed as `v * M`, so `multiply_matrix(a, b)` and `a * b`
+/// apply `a` first.
 #[derive(Clone, Copy, Debug)]
 pub struct Matrix4x4 {
     pub m: [[f32; 4]; 4],
@@ -29,6 +53,34 @@ impl Matrix4x4 {
         mat
     }
 
+    pub fn scale(by: Vector3<f32>) -> Self {
+        let mut mat = Self::identity();
+        mat.m[0][0] = by.x;
+        mat.m[1][1] = by.y;
+        mat.m[2][2] = by.z;
+        mat
+    }
+
+    // ? https://en.wikipedia.org/wiki/Rotation_matrix#Rotation_matrix_from_axis_and_angle
+    /// Rotation of `angle` radians around `axis`, same direction as `rotation_x` and friends.
+    pub fn rotation_axis(axis: Vector3<f32>, angle: f32) -> Self {
+        let Vector3 { x, y, z } = axis.normalize();
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+
+        let mut mat = Self::identity();
+        mat.m[0][0] = t * x * x + cos;
+        mat.m[0][1] = t * x * y + sin * z;
+        mat.m[0][2] = t * x * z - sin * y;
+        mat.m[1][0] = t * x * y - sin * z;
+        mat.m[1][1] = t * y * y + cos;
+        mat.m[1][2] = t * y * z + sin * x;
+        mat.m[2][0] = t * x * z + sin * y;
+        mat.m[2][1] = t * y * z - sin * x;
+        mat.m[2][2] = t * z * z + cos;
+        mat
+    }
+
     pub fn rotation_x(angle: f32) -> Self {
         let mut mat = Self::identity();
         let cos = angle.cos();
@@ -84,7 +136,7 @@ impl Matrix4x4 {
     }
 
     pub fn project(f_near: f32, f_far: f32, f_fov: f32, height: usize, width: usize) -> Matrix4x4{
-        let f_aspect_ratio = (height / width) as f32;
+        let f_aspect_ratio = height as f32 / width as f32;
         let f_fov_rad = 1.0 / (f_fov * 0.5 / 180.0 * PI).tan();
 
         let mut mat_proj = Matrix4x4::identity();
@@ -97,6 +149,44 @@ impl Matrix4x4 {
         mat_proj
     }
 
+    /// Parallel projection showing `size` world units vertically, depth mapped to 0..1.
+    pub fn orthographic(f_near: f32, f_far: f32, size: f32, height: usize, width: usize) -> Matrix4x4 {
+        let f_aspect_ratio = height as f32 / width as f32;
+
+        let mut mat_proj = Matrix4x4::identity();
+        mat_proj.m[0][0] = f_aspect_ratio * 2.0 / size;
+        mat_proj.m[1][1] = 2.0 / size;
+        mat_proj.m[2][2] = 1.0 / (f_far - f_near);
+        mat_proj.m[3][2] = -f_near / (f_far - f_near);
+        mat_proj
+    }
+
+    /// Orthographic projection with depth drawn slanted at `angle` (radians, counter clockwise
+    /// from +x) and shortened by `depth_scale`. Points at depth `focus` are not shifted.
+    #[allow(clippy::too_many_arguments)]
+    pub fn oblique(
+        f_near: f32,
+        f_far: f32,
+        size: f32,
+        angle: f32,
+        depth_scale: f32,
+        focus: f32,
+        height: usize,
+        width: usize,
+    ) -> Matrix4x4 {
+        let shear_x = depth_scale * angle.cos();
+        let shear_y = depth_scale * angle.sin();
+
+        // x' = x + shear_x * (z - focus), same for y, then orthographic
+        let mut shear = Matrix4x4::identity();
+        shear.m[2][0] = shear_x;
+        shear.m[2][1] = shear_y;
+        shear.m[3][0] = -shear_x * focus;
+        shear.m[3][1] = -shear_y * focus;
+
+        Matrix4x4::multiply_matrix(&shear, &Matrix4x4::orthographic(f_near, f_far, size, height, width))
+    }
+
     pub fn point_at(pos: Vector3<f32>, target: Vector3<f32>, up: Vector3<f32>) -> Matrix4x4 {
         // Calculate new forward direction
         let new_forward = (target - pos).normalize();
@@ -116,6 +206,161 @@ impl Matrix4x4 {
 
     }
 
+    /// Inverse transpose of the rotation and scale part, see `Matrix3x3::normal_matrix`.
+    pub fn normal_matrix(&self) -> Matrix3x3 {
+        Matrix3x3::from_matrix4(self).normal_matrix()
+    }
+
+    /// Inverse of a matrix made of scale, rotation and translation, like object world
+    /// matrices. Cheaper than `inverse`, and `quick_inverse` is cheaper still without scale.
+    pub fn affine_inverse(&self) -> Result<Self, SingularMatrixError> {
+        let inv = Matrix3x3::from_matrix4(self).inverse()?;
+        let mut matrix = inv.to_matrix4();
+        let translation = inv.multiply_vec(Vector3::new(self.m[3][0], self.m[3][1], self.m[3][2]));
+        matrix.m[3][0] = -translation.x;
+        matrix.m[3][1] = -translation.y;
+        matrix.m[3][2] = -translation.z;
+        Ok(matrix)
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut matrix = Self::zero();
+        for (r, row) in self.m.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                matrix.m[c][r] = *value;
+            }
+        }
+        matrix
+    }
+
+    // 2x2 determinants of the top two rows (s) and the bottom two (c), shared by
+    // `determinant` and `inverse`
+    fn sub_determinants(&self) -> ([f32; 6], [f32; 6]) {
+        let a = &self.m;
+        let s = [
+            a[0][0] * a[1][1] - a[1][0] * a[0][1],
+            a[0][0] * a[1][2] - a[1][0] * a[0][2],
+            a[0][0] * a[1][3] - a[1][0] * a[0][3],
+            a[0][1] * a[1][2] - a[1][1] * a[0][2],
+            a[0][1] * a[1][3] - a[1][1] * a[0][3],
+            a[0][2] * a[1][3] - a[1][2] * a[0][3],
+        ];
+        let c = [
+            a[2][0] * a[3][1] - a[3][0] * a[2][1],
+            a[2][0] * a[3][2] - a[3][0] * a[2][2],
+            a[2][0] * a[3][3] - a[3][0] * a[2][3],
+            a[2][1] * a[3][2] - a[3][1] * a[2][2],
+            a[2][1] * a[3][3] - a[3][1] * a[2][3],
+            a[2][2] * a[3][3] - a[3][2] * a[2][3],
+        ];
+        (s, c)
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let (s, c) = self.sub_determinants();
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    // ? https://www.geometrictools.com/Documentation/LaplaceExpansionTheorem.pdf
+    /// Inverse of any matrix, projections included. `Err` when the determinant is 0.
+    pub fn inverse(&self) -> Result<Self, SingularMatrixError> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return Err(SingularMatrixError);
+        }
+
+        let a = &self.m;
+        let (s, c) = self.sub_determinants();
+        let inv_det = 1.0 / det;
+        let m = [
+            [
+                a[1][1] * c[5] - a[1][2] * c[4] + a[1][3] * c[3],
+                -a[0][1] * c[5] + a[0][2] * c[4] - a[0][3] * c[3],
+                a[3][1] * s[5] - a[3][2] * s[4] + a[3][3] * s[3],
+                -a[2][1] * s[5] + a[2][2] * s[4] - a[2][3] * s[3],
+            ],
+            [
+                -a[1][0] * c[5] + a[1][2] * c[2] - a[1][3] * c[1],
+                a[0][0] * c[5] - a[0][2] * c[2] + a[0][3] * c[1],
+                -a[3][0] * s[5] + a[3][2] * s[2] - a[3][3] * s[1],
+                a[2][0] * s[5] - a[2][2] * s[2] + a[2][3] * s[1],
+            ],
+            [
+                a[1][0] * c[4] - a[1][1] * c[2] + a[1][3] * c[0],
+                -a[0][0] * c[4] + a[0][1] * c[2] - a[0][3] * c[0],
+                a[3][0] * s[4] - a[3][1] * s[2] + a[3][3] * s[0],
+                -a[2][0] * s[4] + a[2][1] * s[2] - a[2][3] * s[0],
+            ],
+            [
+                -a[1][0] * c[3] + a[1][1] * c[1] - a[1][2] * c[0],
+                a[0][0] * c[3] - a[0][1] * c[1] + a[0][2] * c[0],
+                -a[3][0] * s[3] + a[3][1] * s[1] - a[3][2] * s[0],
+                a[2][0] * s[3] - a[2][1] * s[1] + a[2][2] * s[0],
+            ],
+        ];
+        Ok(Self { m: m.map(|row| row.map(|v| v * inv_det)) })
+    }
+
+    /// View matrix for a camera at `eye` looking at `target`. Left handed cameras look down
+    /// +z like `Camera3D`, right handed ones down -z.
+    pub fn look_at(eye: Vector3<f32>, target: Vector3<f32>, up: Vector3<f32>, handedness: Handedness) -> Self {
+        let forward = (target - eye).normalize();
+        let (z, right) = match handedness {
+            Handedness::Left => (forward, up.cross(forward).normalize()),
+            Handedness::Right => (-forward, forward.cross(up).normalize()),
+        };
+        let y = z.cross(right);
+
+        // Rows of the camera basis, transposed and with the translation undone
+        let mut matrix = Self::identity();
+        for (col, axis) in [right, y, z].into_iter().enumerate() {
+            matrix.m[0][col] = axis.x;
+            matrix.m[1][col] = axis.y;
+            matrix.m[2][col] = axis.z;
+            matrix.m[3][col] = -axis.dot(eye);
+        }
+        matrix
+    }
+
+    /// Perspective projection with the vertical `fov_y` in radians and `aspect` as width /
+    /// height, depth mapped to 0..1 between `near` and `far`.
+    pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32, handedness: Handedness) -> Self {
+        let f = 1.0 / (fov_y * 0.5).tan();
+        let mut matrix = Self::zero();
+        matrix.m[0][0] = f / aspect;
+        matrix.m[1][1] = f;
+        match handedness {
+            Handedness::Left => {
+                matrix.m[2][2] = far / (far - near);
+                matrix.m[2][3] = 1.0;
+                matrix.m[3][2] = -far * near / (far - near);
+            }
+            Handedness::Right => {
+                matrix.m[2][2] = far / (near - far);
+                matrix.m[2][3] = -1.0;
+                matrix.m[3][2] = far * near / (near - far);
+            }
+        }
+        matrix
+    }
+
+    /// Orthographic projection of the box between `left`..`right`, `bottom`..`top` and
+    /// `near`..`far` in view space, depth mapped to 0..1.
+    #[allow(clippy::too_many_arguments)]
+    pub fn ortho(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32, handedness: Handedness) -> Self {
+        let mut matrix = Self::identity();
+        matrix.m[0][0] = 2.0 / (right - left);
+        matrix.m[1][1] = 2.0 / (top - bottom);
+        matrix.m[2][2] = match handedness {
+            Handedness::Left => 1.0 / (far - near),
+            Handedness::Right => -1.0 / (far - near),
+        };
+        matrix.m[3][0] = -(right + left) / (right - left);
+        matrix.m[3][1] = -(top + bottom) / (top - bottom);
+        matrix.m[3][2] = -near / (far - near);
+        matrix
+    }
+
     pub fn quick_inverse(&self) -> Self {
         let mut matrix = Matrix4x4::zero();
 
@@ -140,4 +385,127 @@ impl Matrix4x4 {
 
         matrix
     }
-}
\ No newline at end of file
+}
+
+impl Mul for Matrix4x4 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Matrix4x4::multiply_matrix(&self, &other)
+    }
+}
+
+impl Mul<Matrix4x4> for Vector4<f32> {
+    type Output = Vector4<f32>;
+
+    fn mul(self, matrix: Matrix4x4) -> Self::Output {
+        Matrix4x4::multiply_vec(&matrix, &self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-4;
+
+    fn assert_matrix_eq(a: &Matrix4x4, b: &Matrix4x4) {
+        for (row_a, row_b) in a.m.iter().zip(&b.m) {
+            for (x, y) in row_a.iter().zip(row_b) {
+                assert!((x - y).abs() < EPSILON, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    // Depth after the perspective divide of a view space point on the z axis
+    fn projected_depth(projection: &Matrix4x4, z: f32) -> f32 {
+        let clip = Vector4::new(0.0, 0.0, z, 1.0) * *projection;
+        clip.z / clip.w
+    }
+
+    fn trs() -> Matrix4x4 {
+        Matrix4x4::scale(Vector3::new(2.0, 0.5, 3.0))
+            * Matrix4x4::rotation_axis(Vector3::new(1.0, 2.0, 0.5).normalize(), 0.8)
+            * Matrix4x4::translation(Vector3::new(1.0, -4.0, 7.0))
+    }
+
+    #[test]
+    fn inverse_undoes_the_matrix() {
+        for matrix in [trs(), Matrix4x4::perspective(1.2, 1.5, 0.1, 100.0, Handedness::Left)] {
+            let inverse = matrix.inverse().expect("invertible");
+            assert_matrix_eq(&(matrix * inverse), &Matrix4x4::identity());
+            assert_matrix_eq(&(inverse * matrix), &Matrix4x4::identity());
+        }
+    }
+
+    #[test]
+    fn affine_inverse_matches_inverse() {
+        let matrix = trs();
+        assert_matrix_eq(&matrix.affine_inverse().unwrap(), &matrix.inverse().unwrap());
+    }
+
+    #[test]
+    fn determinant_is_the_volume_scale() {
+        assert!((trs().determinant() - 3.0).abs() < EPSILON);
+        assert!((Matrix4x4::identity().determinant() - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn singular_matrices_have_no_inverse() {
+        let flat = Matrix4x4::scale(Vector3::new(1.0, 0.0, 1.0));
+        assert_eq!(flat.determinant(), 0.0);
+        assert!(flat.inverse().is_err());
+        assert!(flat.affine_inverse().is_err());
+        assert!(Matrix4x4::zero().inverse().is_err());
+    }
+
+    #[test]
+    fn left_handed_look_at_is_the_inverse_of_point_at() {
+        let eye = Vector3::new(1.0, 2.0, -3.0);
+        let target = Vector3::new(-2.0, 0.5, 4.0);
+        let up = Vector3::new(0.0, 1.0, 0.0);
+        let view = Matrix4x4::look_at(eye, target, up, Handedness::Left);
+        assert_matrix_eq(&view, &Matrix4x4::point_at(eye, target, up).quick_inverse());
+
+        // The target ends up straight ahead, down +z
+        let seen = Vector4::from_vector3(target, 1.0) * view;
+        assert!(seen.x.abs() < EPSILON && seen.y.abs() < EPSILON && seen.z > 0.0);
+    }
+
+    #[test]
+    fn right_handed_look_at_looks_down_negative_z() {
+        let (eye, target) = (Vector3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, 0.0));
+        let view = Matrix4x4::look_at(eye, target, Vector3::new(0.0, 1.0, 0.0), Handedness::Right);
+        let seen = Vector4::from_vector3(target, 1.0) * view;
+        assert!(seen.x.abs() < EPSILON && seen.y.abs() < EPSILON);
+        assert!((seen.z + 5.0).abs() < EPSILON);
+
+        // x stays right, so the right of the world is the right of the camera
+        let right = Vector4::new(1.0, 0.0, 0.0, 1.0) * view;
+        assert!(right.x > 0.0);
+    }
+
+    #[test]
+    fn perspective_maps_depth_to_zero_one() {
+        let (near, far) = (0.5, 50.0);
+        let left = Matrix4x4::perspective(1.0, 1.0, near, far, Handedness::Left);
+        assert!(projected_depth(&left, near).abs() < EPSILON);
+        assert!((projected_depth(&left, far) - 1.0).abs() < EPSILON);
+
+        let right = Matrix4x4::perspective(1.0, 1.0, near, far, Handedness::Right);
+        assert!(projected_depth(&right, -near).abs() < EPSILON);
+        assert!((projected_depth(&right, -far) - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn ortho_maps_depth_to_zero_one() {
+        let (near, far) = (0.5, 50.0);
+        let left = Matrix4x4::ortho(-1.0, 1.0, -1.0, 1.0, near, far, Handedness::Left);
+        assert!(projected_depth(&left, near).abs() < EPSILON);
+        assert!((projected_depth(&left, far) - 1.0).abs() < EPSILON);
+
+        let right = Matrix4x4::ortho(-1.0, 1.0, -1.0, 1.0, near, far, Handedness::Right);
+        assert!(projected_depth(&right, -near).abs() < EPSILON);
+        assert!((projected_depth(&right, -far) - 1.0).abs() < EPSILON);
+    }
+}
@@ -2,4 +2,6 @@ pub mod vector_ops;
 pub mod vector2;
 pub mod vector3;
 pub mod vector4;
-pub mod matrix4x4;
\ No newline at end of file
+pub mod matrix4x4;
+pub mod quaternion;
+pub mod matrix3x3;
\ No newline at end of file
@@ -0,0 +1,260 @@
+use std::ops::Mul;
+
+use crate::engine::types::vector::{matrix4x4::Matrix4x4, vector3::Vector3, vector_ops::VectorOps};
+
+/// Rotation stored as a unit quaternion, `w` being the real part. Unlike Euler angles it has
+/// no gimbal lock and interpolates smoothly with `slerp`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Quaternion {
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    /// Rotation of `angle` radians around `axis`, counter clockwise looking down the axis
+    /// like `Matrix4x4::rotation_x` and friends.
+    pub fn from_axis_angle(axis: Vector3<f32>, angle: f32) -> Self {
+        let axis = axis.normalize();
+        let (sin, cos) = (angle * 0.5).sin_cos();
+        Self::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    /// Same rotation as `Object3D::rotation_matrix` for `(pitch X, yaw Y, roll Z)` angles:
+    /// yaw first, then pitch, then roll, around the world axes.
+    pub fn from_euler(rotation: Vector3<f32>) -> Self {
+        let x = Self::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), rotation.x);
+        let y = Self::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), rotation.y);
+        let z = Self::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), rotation.z);
+        z * x * y
+    }
+
+    /// Back to `(axis, angle)`, with the angle in 0..2π. The axis is arbitrary for identity.
+    pub fn to_axis_angle(&self) -> (Vector3<f32>, f32) {
+        let q = self.normalize();
+        let angle = 2.0 * q.w.clamp(-1.0, 1.0).acos();
+        let sin = (1.0 - q.w * q.w).max(0.0).sqrt();
+        if sin < 1e-6 {
+            return (Vector3::new(1.0, 0.0, 0.0), angle);
+        }
+        (Vector3::new(q.x / sin, q.y / sin, q.z / sin), angle)
+    }
+
+    /// Back to the angles of `from_euler`. With the pitch at ±90 degrees yaw and roll turn
+    /// around the same axis, roll is then reported as 0.
+    pub fn to_euler(&self) -> Vector3<f32> {
+        let m = self.to_matrix().m;
+        let pitch = m[1][2].clamp(-1.0, 1.0).asin();
+        if m[1][2].abs() > 0.999_999 {
+            return Vector3::new(pitch, m[2][0].atan2(m[0][0]), 0.0);
+        }
+        Vector3::new(pitch, (-m[0][2]).atan2(m[2][2]), (-m[1][0]).atan2(m[1][1]))
+    }
+
+    pub fn dot(&self, other: &Self) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn magnitude(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Identity if the quaternion is zero.
+    pub fn normalize(&self) -> Self {
+        let len = self.magnitude();
+        if len > 0.0 {
+            Self::new(self.x / len, self.y / len, self.z / len, self.w / len)
+        } else {
+            Self::identity()
+        }
+    }
+
+    /// Opposite rotation, assuming a unit quaternion.
+    pub fn conjugate(&self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    pub fn rotate(&self, v: Vector3<f32>) -> Vector3<f32> {
+        // v + 2w(q x v) + 2q x (q x v)
+        let q = Vector3::new(self.x, self.y, self.z);
+        let t = q.cross(v) * 2.0;
+        v + t * self.w + q.cross(t)
+    }
+
+    /// Row vector matrix like the rest of the engine, `multiply_vec` gives the same as `rotate`.
+    pub fn to_matrix(&self) -> Matrix4x4 {
+        let Self { x, y, z, w } = self.normalize();
+        let mut matrix = Matrix4x4::identity();
+        matrix.m[0][0] = 1.0 - 2.0 * (y * y + z * z);
+        matrix.m[0][1] = 2.0 * (x * y + w * z);
+        matrix.m[0][2] = 2.0 * (x * z - w * y);
+        matrix.m[1][0] = 2.0 * (x * y - w * z);
+        matrix.m[1][1] = 1.0 - 2.0 * (x * x + z * z);
+        matrix.m[1][2] = 2.0 * (y * z + w * x);
+        matrix.m[2][0] = 2.0 * (x * z + w * y);
+        matrix.m[2][1] = 2.0 * (y * z - w * x);
+        matrix.m[2][2] = 1.0 - 2.0 * (x * x + y * y);
+        matrix
+    }
+
+    /// Normalized linear interpolation along the shortest path. Cheaper than `slerp` but the
+    /// speed is not constant over `t`.
+    pub fn nlerp(a: &Self, b: &Self, t: f32) -> Self {
+        let b = if a.dot(b) < 0.0 { b.negate() } else { *b };
+        Self::new(
+            a.x + (b.x - a.x) * t,
+            a.y + (b.y - a.y) * t,
+            a.z + (b.z - a.z) * t,
+            a.w + (b.w - a.w) * t,
+        )
+        .normalize()
+    }
+
+    /// Spherical interpolation along the shortest path at constant angular speed.
+    pub fn slerp(a: &Self, b: &Self, t: f32) -> Self {
+        let mut cos = a.dot(b);
+        let b = if cos < 0.0 {
+            cos = -cos;
+            b.negate()
+        } else {
+            *b
+        };
+
+        // Nearly the same rotation, the sine below would be ~0
+        if cos > 0.9995 {
+            return Self::nlerp(a, &b, t);
+        }
+
+        let angle = cos.acos();
+        let sin = angle.sin();
+        let (wa, wb) = (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin);
+        Self::new(
+            a.x * wa + b.x * wb,
+            a.y * wa + b.y * wb,
+            a.z * wa + b.z * wb,
+            a.w * wa + b.w * wb,
+        )
+    }
+
+    // Same rotation, other hemisphere
+    fn negate(&self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, -self.w)
+    }
+}
+
+/// `a * b` rotates by `b` first and then by `a`, the reverse of `Matrix4x4::multiply_matrix`.
+impl Mul for Quaternion {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Self {
+            x: self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            y: self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            z: self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            w: self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+        }
+    }
+}
+
+impl Mul<Vector3<f32>> for Quaternion {
+    type Output = Vector3<f32>;
+
+    fn mul(self, v: Vector3<f32>) -> Self::Output {
+        self.rotate(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use super::*;
+    use crate::engine::types::{object3d::Object3D, vector::vector4::Vector4};
+
+    const EPSILON: f32 = 1e-4;
+
+    fn assert_vec_eq(a: Vector3<f32>, b: Vector3<f32>) {
+        assert!((a - b).magnitude() < EPSILON, "{:?} != {:?}", a, b);
+    }
+
+    // q and -q are the same rotation
+    fn assert_same_rotation(a: &Quaternion, b: &Quaternion) {
+        assert!(a.dot(b).abs() > 1.0 - EPSILON, "{:?} != {:?}", a, b);
+    }
+
+    fn some_angles() -> [Vector3<f32>; 4] {
+        [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.3, -1.2, 0.7),
+            Vector3::new(-0.9, 2.5, -0.4),
+            Vector3::new(1.1, 0.2, 3.0),
+        ]
+    }
+
+    #[test]
+    fn from_euler_matches_object_rotation_matrix() {
+        for angles in some_angles() {
+            let expected = Object3D::new(Vector3::new(0.0, 0.0, 0.0), angles).rotation_matrix();
+            let matrix = Quaternion::from_euler(angles).to_matrix();
+            for (row_a, row_b) in matrix.m.iter().zip(&expected.m) {
+                for (x, y) in row_a.iter().zip(row_b) {
+                    assert!((x - y).abs() < EPSILON, "{:?}: {:?} != {:?}", angles, matrix, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn to_euler_round_trips() {
+        for angles in some_angles() {
+            let q = Quaternion::from_euler(angles);
+            assert_same_rotation(&Quaternion::from_euler(q.to_euler()), &q);
+        }
+    }
+
+    #[test]
+    fn to_matrix_rotates_like_rotate() {
+        let q = Quaternion::from_axis_angle(Vector3::new(1.0, -2.0, 0.5).normalize(), 1.3);
+        let v = Vector3::new(0.4, 1.5, -2.0);
+        let by_matrix = Matrix4x4::multiply_vec(&q.to_matrix(), &Vector4::from_vector3(v, 1.0));
+        assert_vec_eq(by_matrix.to_vector3(), q.rotate(v));
+    }
+
+    #[test]
+    fn axis_angle_round_trips() {
+        let axis = Vector3::new(0.0, 1.0, 1.0).normalize();
+        let (back_axis, angle) = Quaternion::from_axis_angle(axis, 2.0).to_axis_angle();
+        assert_vec_eq(back_axis, axis);
+        assert!((angle - 2.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn slerp_moves_at_constant_speed() {
+        let (a, b) = (Quaternion::identity(), Quaternion::from_axis_angle(Vector3::up(), FRAC_PI_2));
+        assert_same_rotation(&Quaternion::slerp(&a, &b, 0.0), &a);
+        assert_same_rotation(&Quaternion::slerp(&a, &b, 1.0), &b);
+        assert_same_rotation(
+            &Quaternion::slerp(&a, &b, 0.5),
+            &Quaternion::from_axis_angle(Vector3::up(), FRAC_PI_4),
+        );
+    }
+
+    #[test]
+    fn slerp_takes_the_shortest_path() {
+        let a = Quaternion::identity();
+        let b = Quaternion::from_axis_angle(Vector3::up(), FRAC_PI_2).negate();
+        assert_same_rotation(
+            &Quaternion::slerp(&a, &b, 0.5),
+            &Quaternion::from_axis_angle(Vector3::up(), FRAC_PI_4),
+        );
+    }
+}
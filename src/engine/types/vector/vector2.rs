@@ -1,7 +1,10 @@
-use num_traits::{Float, NumCast, One, Zero};
-use std::ops::{Add, Div, Mul, Sub};
+use num_traits::{Float, Num, NumCast};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use crate::engine::types::vector::vector_ops::VectorOps;
+use crate::engine::types::vector::{
+    vector_ops::{max_of, min_of, VectorOps},
+    vector3::Vector3,
+};
 
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -15,9 +18,23 @@ impl<T> Vector2<T> {
         Self { x, y }
     }
 }
+
+// Swizzles and conversions between sizes
+impl<T> Vector2<T>
+where
+    T: Copy,
+{
+    pub fn yx(self) -> Self {
+        Self::new(self.y, self.x)
+    }
+
+    pub fn extend(self, z: T) -> Vector3<T> {
+        Vector3::new(self.x, self.y, z)
+    }
+}
 impl<T> VectorOps<T> for Vector2<T>
 where
-    T: Float,
+    T: Num + Copy + PartialOrd,
 {
     fn add(self, other: Self) -> Self {
         self + other
@@ -35,10 +52,27 @@ where
         self.x * other.x + self.y * other.y
     }
 
-    fn magnitude(self) -> T {
+    fn magnitude(self) -> T
+    where
+        T: Float,
+    {
         (self.x * self.x + self.y * self.y).sqrt()
     }
 
+    fn min(self, other: Self) -> Self {
+        Self {
+            x: min_of(self.x, other.x),
+            y: min_of(self.y, other.y),
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            x: max_of(self.x, other.x),
+            y: max_of(self.y, other.y),
+        }
+    }
+
     fn up() -> Self {
         Self {
             x: T::zero(),
@@ -171,3 +205,71 @@ where
         }
     }
 }
+
+impl<T> Neg for Vector2<T>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl<T> Sub<T> for Vector2<T>
+where
+    T: Sub<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn sub(self, factor: T) -> Self::Output {
+        Self {
+            x: self.x - factor,
+            y: self.y - factor,
+        }
+    }
+}
+
+impl<T> AddAssign for Vector2<T>
+where
+    T: AddAssign,
+{
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T> SubAssign for Vector2<T>
+where
+    T: SubAssign,
+{
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T> MulAssign<T> for Vector2<T>
+where
+    T: MulAssign + Copy,
+{
+    fn mul_assign(&mut self, other: T) {
+        self.x *= other;
+        self.y *= other;
+    }
+}
+
+impl<T> DivAssign<T> for Vector2<T>
+where
+    T: DivAssign + Copy,
+{
+    fn div_assign(&mut self, other: T) {
+        self.x /= other;
+        self.y /= other;
+    }
+}
@@ -1,7 +1,11 @@
-use num_traits::{Float, NumCast, One, Zero};
-use std::ops::{Add, Div, Mul, Sub};
+use num_traits::{Float, Num, NumCast};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use crate::engine::types::vector::{vector2::Vector2, vector_ops::VectorOps};
+use crate::engine::types::vector::{
+    vector2::Vector2,
+    vector4::Vector4,
+    vector_ops::{max_of, min_of, VectorOps},
+};
 
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -18,9 +22,32 @@ impl<T> Vector3<T> {
 
     
 }
+
+// Swizzles and conversions between sizes
+impl<T> Vector3<T>
+where
+    T: Copy,
+{
+    pub fn xy(self) -> Vector2<T> {
+        Vector2::new(self.x, self.y)
+    }
+
+    pub fn xz(self) -> Vector2<T> {
+        Vector2::new(self.x, self.z)
+    }
+
+    pub fn yz(self) -> Vector2<T> {
+        Vector2::new(self.y, self.z)
+    }
+
+    /// Homogeneous coordinates: `w` 1 for points, 0 for directions.
+    pub fn extend(self, w: T) -> Vector4<T> {
+        Vector4::new(self.x, self.y, self.z, w)
+    }
+}
 impl<T> VectorOps<T> for Vector3<T>
 where
-    T: Float,
+    T: Num + Copy + PartialOrd,
 {
     fn add(self, other: Self) -> Self {
         self + other
@@ -38,10 +65,29 @@ where
         self.x * other.x + self.y * other.y + self.z * other.z
     }
 
-    fn magnitude(self) -> T {
+    fn magnitude(self) -> T
+    where
+        T: Float,
+    {
         (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
     }
 
+    fn min(self, other: Self) -> Self {
+        Self {
+            x: min_of(self.x, other.x),
+            y: min_of(self.y, other.y),
+            z: min_of(self.z, other.z),
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            x: max_of(self.x, other.x),
+            y: max_of(self.y, other.y),
+            z: max_of(self.z, other.z),
+        }
+    }
+
     fn up() -> Self {
         Self {
             x: T::zero(),
@@ -209,8 +255,82 @@ where
     }
 }
 
-impl Into<Vector2<i32>> for Vector3<f32> {
-    fn into(self) -> Vector2<i32> {
-        Vector2 { x: self.x as i32, y: self.y as i32 }
+impl From<Vector3<f32>> for Vector2<i32> {
+    fn from(val: Vector3<f32>) -> Self {
+        Vector2 { x: val.x as i32, y: val.y as i32 }
+    }
+}
+
+impl<T> Neg for Vector3<T>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+impl<T> Sub<T> for Vector3<T>
+where
+    T: Sub<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn sub(self, factor: T) -> Self::Output {
+        Self {
+            x: self.x - factor,
+            y: self.y - factor,
+            z: self.z - factor,
+        }
+    }
+}
+
+impl<T> AddAssign for Vector3<T>
+where
+    T: AddAssign,
+{
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+        self.z += other.z;
     }
-}
\ No newline at end of file
+}
+
+impl<T> SubAssign for Vector3<T>
+where
+    T: SubAssign,
+{
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+        self.z -= other.z;
+    }
+}
+
+impl<T> MulAssign<T> for Vector3<T>
+where
+    T: MulAssign + Copy,
+{
+    fn mul_assign(&mut self, other: T) {
+        self.x *= other;
+        self.y *= other;
+        self.z *= other;
+    }
+}
+
+impl<T> DivAssign<T> for Vector3<T>
+where
+    T: DivAssign + Copy,
+{
+    fn div_assign(&mut self, other: T) {
+        self.x /= other;
+        self.y /= other;
+        self.z /= other;
+    }
+}
@@ -1,7 +1,11 @@
-use num_traits::{Float, NumCast, One, Zero};
-use std::ops::{Add, Div, Mul, Sub};
+use num_traits::{Float, Num, NumCast};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use crate::engine::types::vector::{vector_ops::VectorOps, vector2::Vector2, vector3::Vector3};
+use crate::engine::types::vector::{
+    vector_ops::{max_of, min_of, VectorOps},
+    vector2::Vector2,
+    vector3::Vector3,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Vector4<T> {
@@ -24,19 +28,6 @@ where
         }
     }
 
-    pub fn new(x: T, y: T, z: T, w: T) -> Self {
-        Self { x, y, z, w }
-    }
-
-    pub fn from_vector3(v: Vector3<T>, w: T) -> Self {
-        Self {
-            x: v.x,
-            y: v.y,
-            z: v.z,
-            w,
-        }
-    }
-
     /// Divide x, y, z por w si w != 0, para hacer proyección en espacio 3D
     pub fn perspective_divide(&self) -> Vector4<T>
     where
@@ -61,14 +52,34 @@ where
         }
     }
 
-    pub fn cross(self, other: Self) -> Self {
+    /// Cross product of the xyz parts, `w` is ignored. A direction, so it is not a `Vector4`.
+    pub fn cross3(self, other: Self) -> Vector3<T> {
+        self.to_vector3().cross(other.to_vector3())
+    }
+}
+
+impl<T> Vector4<T> {
+    pub fn new(x: T, y: T, z: T, w: T) -> Self {
+        Self { x, y, z, w }
+    }
+
+}
+
+// Swizzles and conversions between sizes
+impl<T> Vector4<T>
+where
+    T: Copy,
+{
+    pub fn from_vector3(v: Vector3<T>, w: T) -> Self {
         Self {
-            x: self.y * other.z - self.z * other.y,
-            y: self.z * other.x - self.x * other.z,
-            z: self.x * other.y - self.y * other.x,
-            w: self.w,
+            x: v.x,
+            y: v.y,
+            z: v.z,
+            w,
         }
     }
+
+    /// Drops `w` as is, see `perspective_divide` for projected points.
     pub fn to_vector3(self) -> Vector3<T> {
         Vector3 {
             x: self.x,
@@ -76,10 +87,31 @@ where
             z: self.z,
         }
     }
+
+    pub fn xy(self) -> Vector2<T> {
+        Vector2::new(self.x, self.y)
+    }
+}
+
+impl<T> Vector4<T>
+where
+    T: Copy + NumCast,
+{
+    pub fn cast<G>(&self) -> Option<Vector4<G>>
+    where
+        G: NumCast,
+    {
+        Some(Vector4 {
+            x: NumCast::from(self.x)?,
+            y: NumCast::from(self.y)?,
+            z: NumCast::from(self.z)?,
+            w: NumCast::from(self.w)?,
+        })
+    }
 }
 impl<T> VectorOps<T> for Vector4<T>
 where
-    T: Float,
+    T: Num + Copy + PartialOrd,
 {
     fn add(self, other: Self) -> Self {
         self + other
@@ -97,10 +129,31 @@ where
         self.x * other.x + self.y * other.y + self.z * other.z
     }
 
-    fn magnitude(self) -> T {
+    fn magnitude(self) -> T
+    where
+        T: Float,
+    {
         (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
     }
 
+    fn min(self, other: Self) -> Self {
+        Self {
+            x: min_of(self.x, other.x),
+            y: min_of(self.y, other.y),
+            z: min_of(self.z, other.z),
+            w: min_of(self.w, other.w),
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            x: max_of(self.x, other.x),
+            y: max_of(self.y, other.y),
+            z: max_of(self.z, other.z),
+            w: max_of(self.w, other.w),
+        }
+    }
+
     fn up() -> Self {
         Self {
             x: T::zero(),
@@ -244,11 +297,91 @@ where
     }
 }
 
-impl Into<Vector2<i32>> for Vector4<f32> {
-    fn into(self) -> Vector2<i32> {
+impl From<Vector4<f32>> for Vector2<i32> {
+    fn from(val: Vector4<f32>) -> Self {
         Vector2 {
-            x: self.x as i32,
-            y: self.y as i32,
+            x: val.x as i32,
+            y: val.y as i32,
         }
     }
 }
+
+impl<T> Neg for Vector4<T>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+            w: -self.w,
+        }
+    }
+}
+
+impl<T> Sub<T> for Vector4<T>
+where
+    T: Sub<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn sub(self, factor: T) -> Self::Output {
+        Self {
+            x: self.x - factor,
+            y: self.y - factor,
+            z: self.z - factor,
+            w: self.w - factor,
+        }
+    }
+}
+
+impl<T> AddAssign for Vector4<T>
+where
+    T: AddAssign,
+{
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+        self.z += other.z;
+        self.w += other.w;
+    }
+}
+
+impl<T> SubAssign for Vector4<T>
+where
+    T: SubAssign,
+{
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+        self.z -= other.z;
+        self.w -= other.w;
+    }
+}
+
+impl<T> MulAssign<T> for Vector4<T>
+where
+    T: MulAssign + Copy,
+{
+    fn mul_assign(&mut self, other: T) {
+        self.x *= other;
+        self.y *= other;
+        self.z *= other;
+        self.w *= other;
+    }
+}
+
+impl<T> DivAssign<T> for Vector4<T>
+where
+    T: DivAssign + Copy,
+{
+    fn div_assign(&mut self, other: T) {
+        self.x /= other;
+        self.y /= other;
+        self.z /= other;
+        self.w /= other;
+    }
+}
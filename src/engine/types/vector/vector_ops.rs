@@ -1,20 +1,55 @@
-use num_traits::{Float, One, Zero};
+use num_traits::{Float, Num};
 
+/// Shared by `Vector2`, `Vector3` and `Vector4`. Integer vectors get the arithmetic and
+/// component-wise helpers, lengths and directions need a `Float`.
 pub trait VectorOps<T>: Sized + Copy
 where
-    T: Float,
+    T: Num + Copy + PartialOrd,
 {
     fn add(self, other: Self) -> Self;
     fn sub(self, other: Self) -> Self;
     fn scale(self, factor: T) -> Self;
     fn dot(self, other: Self) -> T;
 
-    fn magnitude(self) -> T;
-    fn distance(self, other: Self) -> T {
+    /// Component-wise minimum.
+    fn min(self, other: Self) -> Self;
+    /// Component-wise maximum.
+    fn max(self, other: Self) -> Self;
+
+    /// Each component kept between the ones of `low` and `high`.
+    fn clamp(self, low: Self, high: Self) -> Self {
+        self.max(low).min(high)
+    }
+
+    /// `self` at `t` = 0, `other` at `t` = 1.
+    fn lerp(self, other: Self, t: T) -> Self {
+        self.add(other.sub(self).scale(t))
+    }
+
+    /// Mirrors the vector off a surface with unit `normal`, like light bouncing off it.
+    fn reflect(self, normal: Self) -> Self {
+        let d = self.dot(normal);
+        self.sub(normal.scale(d + d))
+    }
+
+    fn magnitude_squared(self) -> T {
+        self.dot(self)
+    }
+
+    fn magnitude(self) -> T
+    where
+        T: Float;
+    fn distance(self, other: Self) -> T
+    where
+        T: Float,
+    {
         self.sub(other).magnitude()
     }
 
-    fn normalize(self) -> Self {
+    fn normalize(self) -> Self
+    where
+        T: Float,
+    {
         let len = self.magnitude();
         if len > T::zero() {
             self.scale(T::one() / len)
@@ -26,3 +61,13 @@ where
     fn up() -> Self;
     fn zero() -> Self;
 }
+
+// Component-wise helpers for the `min` and `max` implementations. A value that can't be
+// compared with itself (NaN) loses, like with `f32::min`
+pub(crate) fn min_of<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a || a.partial_cmp(&a).is_none() { b } else { a }
+}
+
+pub(crate) fn max_of<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a || a.partial_cmp(&a).is_none() { b } else { a }
+}
@@ -2,7 +2,7 @@ use std::time::Instant;
 
 use minifb::{Window, WindowOptions};
 
-use crate::{app::MyApp, engine::rendering::palettes::PALETTE_DEFAULT};
+use crate::{app::MyApp, engine::{loader::palette_loader::PaletteLoader, rendering::palettes::PALETTE_DEFAULT}};
 
 
 const WIDTH: usize = 500;
@@ -22,14 +22,16 @@ fn main() {
         }
     }
 
+    // Scaling is done by the renderer fit mode, see `FitMode`
     let window = Window::new(
         "Cube",
-        WIDTH,
-        HEIGHT,
+        WIDTH * 2,
+        HEIGHT * 2,
         WindowOptions {
             borderless: false,
-            resize: false,
-            scale: minifb::Scale::X2, 
+            resize: true,
+            scale: minifb::Scale::X1,
+            scale_mode: minifb::ScaleMode::UpperLeft,
             ..WindowOptions::default()
         },
     )
@@ -43,6 +45,14 @@ fn main() {
 
     let mut app= MyApp::new(WIDTH, HEIGHT, window);
 
+    // Optional palette file (.gpl, .pal, .hex or an image strip) as first argument
+    if let Some(path) = std::env::args().nth(1) {
+        match PaletteLoader::from_file(&path) {
+            Ok(palette) => app.palette = Box::new(palette),
+            Err(e) => eprintln!("Can't load palette '{}': {}", path, e),
+        }
+    }
+
 
     // ? -------------------------------------------------------------------------------------------------
     let mut last_frame = Instant::now();